@@ -1,12 +1,19 @@
 mod render;
+mod uci;
 
+use std::env;
 use std::io::{self, Write};
+use std::process;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
 
 use colored::*;
 
-use game::{Board, Color};
+use game::{Board, Color, Game, Move, Search, SearchLimits};
+
+/// How long the computer thinks about its moves.
+const ENGINE_MOVE_TIME: Duration = Duration::from_secs(2);
 
 pub fn prompt(msg: &str) -> io::Result<String> {
     print!("{}", msg.blue());
@@ -18,32 +25,74 @@ pub fn prompt(msg: &str) -> io::Result<String> {
     Ok(String::from(user_input.trim()))
 }
 
-fn single_turn(board: &mut Board) -> Result<Board> {
-    let r = prompt("Enter your move: ").unwrap();
-    let v: Vec<_> = r.split(' ').collect();
-    if v.len() != 2 {
-        return Err(anyhow!("Invalid move"));
+fn single_turn(game: &mut Game, search: &mut Search) -> Result<()> {
+    let r = prompt(
+        "Enter your move (e.g. Nf3, e2 e4, e8=Q, e7 e8 q, undo, draw, or go to let the computer play): ",
+    )
+    .unwrap();
+    if r == "undo" {
+        game.undo().ok_or_else(|| anyhow!("No move to undo"))?;
+        return Ok(());
+    }
+    if r == "draw" {
+        game.claim_draw()
+            .ok_or_else(|| anyhow!("No draw to claim"))?;
+        return Ok(());
     }
 
-    let new_position = board.move_notation(v[0], v[1])?;
-    Ok(new_position)
+    let v: Vec<_> = r.split(' ').collect();
+    let mv = match v.len() {
+        1 if v[0] == "go" => engine_move(game, search)?,
+        1 => game.position().parse_san(v[0])?,
+        2 => Move::from_notation(v[0], v[1], None)?,
+        3 if v[2].len() == 1 => Move::from_notation(v[0], v[1], v[2].chars().next())?,
+        _ => return Err(anyhow!("Invalid move")),
+    };
+
+    let san = game.position().to_san(&mv)?;
+    game.play(&mv)?;
+    println!("{}", san.green());
+    Ok(())
 }
 
-fn main() {
-    let mut board = game::board_with_setup();
-    let fen = board.to_fen();
+/// Searches the best move in the current position of the game.
+fn engine_move(game: &Game, search: &mut Search) -> Result<Move> {
+    let result = search.run_game(game, &SearchLimits::time(ENGINE_MOVE_TIME));
+    println!(
+        "Depth {}, score {}, {} nodes in {:?}, {:.0}% hash hits, {:.0}% first-move cutoffs",
+        result.depth,
+        result.score,
+        result.nodes,
+        result.time,
+        result.table_stats.hit_rate() * 100.0,
+        result.ordering_stats.first_move_cutoff_rate() * 100.0
+    );
+    result.best_move.ok_or_else(|| anyhow!("No move to play"))
+}
+
+fn play() {
+    let mut game = Game::new();
+    let mut search = Search::new();
+    let fen = game.position().to_fen();
     println!("{}", fen);
 
-    loop {
-        render::render_board(&board, Color::Black, true);
-        loop {
-            match single_turn(&mut board) {
-                Ok(b) => {
-                    // Turn successful, update the board.
-                    board = b;
+    while !game.status().is_over() {
+        render::render_board(game.position(), Color::Black, true);
+        if game.position().is_check() {
+            println!("{}", "Check!".yellow());
+        }
+        if let Some(reason) = game.claimable_draw() {
+            println!(
+                "{}",
+                format!("A draw can be claimed ({}).", reason).yellow()
+            );
+        }
 
+        loop {
+            match single_turn(&mut game, &mut search) {
+                Ok(()) => {
                     // Get FEN notation.
-                    let fen = board.to_fen();
+                    let fen = game.position().to_fen();
                     println!("{}", fen);
                     break;
                 }
@@ -53,4 +102,51 @@ fn main() {
             }
         }
     }
+
+    render::render_board(game.position(), Color::Black, true);
+    println!("{}", game.status().to_string().green());
+}
+
+/// Counts the nodes of the move tree from a position, split by first move.
+///
+/// Usage: `perft <depth> [fen]`
+fn perft(args: &[String]) -> Result<()> {
+    let depth = args
+        .first()
+        .ok_or_else(|| anyhow!("Usage: perft <depth> [fen]"))?
+        .parse::<usize>()?;
+    let board = match args.len() {
+        1 => game::board_with_setup(),
+        _ => Board::from_fen(args[1..].join(" ")).map_err(|e| anyhow!("Invalid FEN: {:?}", e))?,
+    };
+
+    let start = Instant::now();
+    let mut total = 0;
+    for (mv, nodes) in board.perft_divide(depth) {
+        println!("{}: {}", mv, nodes);
+        total += nodes;
+    }
+
+    println!();
+    println!("Nodes searched: {}", total);
+    println!("Time: {:?}", start.elapsed());
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_ref) {
+        None => {
+            play();
+            Ok(())
+        }
+        Some("perft") => perft(&args[1..]),
+        Some("uci") => uci::run(),
+        Some(command) => Err(anyhow!("Unknown command: {}", command)),
+    };
+
+    if let Err(e) = result {
+        println!("{}", e.to_string().red());
+        process::exit(1);
+    }
 }
@@ -2,7 +2,9 @@ use std::convert::TryFrom;
 
 use colored::*;
 
-use game::{constants::BOARD_DIMENSION, Board, Color as GameColor, Column, Piece, PieceType};
+use game::{
+    constants::BOARD_DIMENSION, Board, Color as GameColor, Column, Piece, PieceType, Square,
+};
 
 fn render_piece(p: &Piece, unicode: bool) -> String {
     if unicode {
@@ -57,7 +59,8 @@ pub fn render_board(board: &Board, perspective: GameColor, unicode: bool) {
     for row_idx in row_range.iter() {
         let mut row_str = String::default();
         for col_idx in coll_range.iter() {
-            match &board.board[*col_idx][*row_idx] {
+            let square = Square::new(Column::try_from(*col_idx).unwrap(), *row_idx);
+            match board.at(&square) {
                 Some(p) => row_str += &format!("{} ", render_piece(p, unicode)),
                 None => {
                     row_str += ". ";
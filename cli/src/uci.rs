@@ -0,0 +1,544 @@
+//! The Universal Chess Interface, through which chess GUIs and tournament managers play against
+//! the engine.
+//!
+//! Commands are read from the standard input, one per line, and answers written to the standard
+//! output. Searches run on their own thread, so that commands like `stop` and `isready` are
+//! answered while the engine thinks.
+
+use std::io::{self, BufRead};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use game::{
+    board_with_setup, Board, Color, Game, Move, Score, Search, SearchLimits, SearchOptions,
+    SearchResult, DEFAULT_TABLE_SIZE_MB, NULL_MOVE_UCI,
+};
+
+const ENGINE_NAME: &str = "chessengine";
+const ENGINE_AUTHOR: &str = "William Dussault";
+
+/// The largest transposition table the GUI can ask for, in megabytes.
+const MAX_TABLE_SIZE_MB: usize = 4096;
+
+/// The number of moves the time left is split between, when the GUI doesn't tell how many
+/// remain until the next time control.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/// Time kept aside on the clock for the move to reach the GUI.
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+
+/// The words of a `go` command that start a parameter.
+const GO_PARAMETERS: [&str; 12] = [
+    "searchmoves",
+    "ponder",
+    "wtime",
+    "btime",
+    "winc",
+    "binc",
+    "movestogo",
+    "depth",
+    "nodes",
+    "mate",
+    "movetime",
+    "infinite",
+];
+
+/// Gives access to one of the switches of the selective search.
+type Switch = fn(&mut SearchOptions) -> &mut bool;
+
+/// The switches of the selective search, by option name.
+const SEARCH_OPTIONS: [(&str, Switch); 6] = [
+    ("NullMovePruning", |options| &mut options.null_move_pruning),
+    ("LateMoveReductions", |options| {
+        &mut options.late_move_reductions
+    }),
+    ("FutilityPruning", |options| &mut options.futility_pruning),
+    ("ReverseFutilityPruning", |options| {
+        &mut options.reverse_futility_pruning
+    }),
+    ("CheckExtensions", |options| &mut options.check_extensions),
+    ("AspirationWindows", |options| {
+        &mut options.aspiration_windows
+    }),
+];
+
+/// The parameters of a `go` command.
+#[derive(Debug, Default)]
+struct Go {
+    limits: SearchLimits,
+
+    /// Search until told to stop, whatever the limits.
+    infinite: bool,
+
+    /// Search while the opponent thinks about its move, the one expected after ours being
+    /// already played.
+    ponder: bool,
+}
+
+/// Splits the time left on the clock between the moves left to play until the next time
+/// control.
+fn allot_time(time_left: Duration, increment: Duration, moves_to_go: Option<u32>) -> Duration {
+    let moves = moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+    let time = time_left / moves + increment * 3 / 4;
+    time.min(time_left.checked_sub(MOVE_OVERHEAD).unwrap_or_default())
+}
+
+fn parse_millis(value: &str) -> Option<Duration> {
+    // Clocks running out of time can go negative.
+    let millis = value.parse::<i64>().ok()?;
+    Some(Duration::from_millis(millis.max(0) as u64))
+}
+
+/// Parses the parameters of a `go` command, for the side to play.
+///
+/// Unknown parameters and values that don't parse are skipped, so that a search always starts.
+fn parse_go(args: &[&str], to_play: Color) -> Go {
+    let mut go = Go::default();
+    let (mut time_left, mut increment) = (None, Duration::default());
+    let mut moves_to_go = None;
+    let mut mate = None;
+
+    let mut args = args.iter().copied().peekable();
+    while let Some(name) = args.next() {
+        match name {
+            "infinite" => go.infinite = true,
+            "ponder" => go.ponder = true,
+            "searchmoves" => {
+                while let Some(uci) = args.next_if(|arg| !GO_PARAMETERS.contains(arg)) {
+                    if let Ok(Some(mv)) = Move::from_uci(uci) {
+                        go.limits.root_moves.push(mv);
+                    }
+                }
+            }
+            _ => {
+                let value = match args.next_if(|arg| !GO_PARAMETERS.contains(arg)) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                match (name, to_play) {
+                    ("wtime", Color::White) | ("btime", Color::Black) => {
+                        time_left = parse_millis(value)
+                    }
+                    ("winc", Color::White) | ("binc", Color::Black) => {
+                        increment = parse_millis(value).unwrap_or_default()
+                    }
+                    ("movestogo", _) => moves_to_go = value.parse().ok(),
+                    ("depth", _) => go.limits.depth = value.parse().ok(),
+                    ("nodes", _) => go.limits.nodes = value.parse().ok(),
+                    ("mate", _) => mate = value.parse::<usize>().ok().filter(|moves| *moves > 0),
+                    ("movetime", _) => go.limits.time = parse_millis(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // A mate in n moves is found by searching 2n - 1 plies.
+    if let Some(moves) = mate {
+        let depth = 2 * moves - 1;
+        go.limits.depth = Some(go.limits.depth.map_or(depth, |d| d.min(depth)));
+    }
+    if go.limits.time.is_none() {
+        go.limits.time = time_left.map(|time_left| allot_time(time_left, increment, moves_to_go));
+    }
+    go
+}
+
+/// Formats a score as an `info` line expects it.
+fn format_score(score: Score) -> String {
+    match score {
+        Score::Centipawns(cp) => format!("cp {}", cp),
+        Score::Mate(moves) => format!("mate {}", moves),
+    }
+}
+
+/// Reports the result of a completed iteration to the GUI.
+fn send_info(result: &SearchResult) {
+    let micros = result.time.as_micros() as u64;
+    let pv: Vec<_> = result.pv.iter().map(Move::to_uci).collect();
+    println!(
+        "info depth {} score {} nodes {} nps {} time {} hashfull {} pv {}",
+        result.depth,
+        format_score(result.score),
+        result.nodes,
+        result.nodes * 1_000_000 / micros.max(1),
+        micros / 1000,
+        result.table_stats.hashfull,
+        pv.join(" ")
+    );
+}
+
+/// Sends the move chosen by a search, with the answer expected from the opponent to ponder on.
+fn send_best_move(result: &SearchResult) {
+    let best_move = result
+        .best_move
+        .as_ref()
+        .map(Move::to_uci)
+        .unwrap_or_else(|| NULL_MOVE_UCI.to_string());
+    match result.pv.get(1) {
+        Some(ponder) => println!("bestmove {} ponder {}", best_move, ponder),
+        None => println!("bestmove {}", best_move),
+    }
+}
+
+/// A search running on its own thread, which sends its best move once it is done.
+struct Thinking {
+    thread: JoinHandle<Search>,
+
+    /// Lets the thread send its best move, which it holds back while pondering or searching
+    /// infinitely.
+    release: Sender<()>,
+}
+
+/// The state of the engine between commands.
+struct Engine {
+    game: Game,
+
+    /// The search, `None` while it runs on its own thread.
+    search: Option<Search>,
+    stop: Arc<AtomicBool>,
+    ponder: Arc<AtomicBool>,
+    thinking: Option<Thinking>,
+}
+
+impl Engine {
+    fn new() -> Self {
+        let search = Search::new();
+        Engine {
+            game: Game::new(),
+            stop: search.stop_flag(),
+            ponder: search.ponder_flag(),
+            search: Some(search),
+            thinking: None,
+        }
+    }
+
+    /// Handles a command, returning whether to keep reading them.
+    fn handle(&mut self, line: &str) -> Result<bool> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Ok(true),
+        };
+
+        match command {
+            "uci" => self.uci(),
+            "isready" => println!("readyok"),
+            "setoption" => self.set_option(args)?,
+            "ucinewgame" => {
+                self.stop().clear_table();
+                self.game = Game::new();
+            }
+            "position" => self.position(args)?,
+            "go" => self.go(args),
+            "stop" => {
+                self.stop();
+            }
+            "ponderhit" => self.ponderhit(),
+            "quit" => {
+                self.stop();
+                return Ok(false);
+            }
+            _ => return Err(anyhow!("Unknown command: {}", command)),
+        }
+        Ok(true)
+    }
+
+    fn uci(&self) {
+        println!("id name {}", ENGINE_NAME);
+        println!("id author {}", ENGINE_AUTHOR);
+        println!(
+            "option name Hash type spin default {} min 1 max {}",
+            DEFAULT_TABLE_SIZE_MB, MAX_TABLE_SIZE_MB
+        );
+        println!("option name Clear Hash type button");
+        println!("option name Ponder type check default false");
+        for (name, option) in SEARCH_OPTIONS.iter() {
+            let default = *option(&mut SearchOptions::default());
+            println!("option name {} type check default {}", name, default);
+        }
+        println!("uciok");
+    }
+
+    /// Handles `setoption name <name> [value <value>]`.
+    fn set_option(&mut self, args: &[&str]) -> Result<()> {
+        let value_start = args
+            .iter()
+            .position(|word| *word == "value")
+            .unwrap_or(args.len());
+        let name = match args.split_first() {
+            Some((&"name", _)) => args[1..value_start].join(" "),
+            _ => return Err(anyhow!("Usage: setoption name <name> [value <value>]")),
+        };
+        let value = args.get(value_start + 1..).unwrap_or_default().join(" ");
+
+        let search = self.stop();
+        if name.eq_ignore_ascii_case("Hash") {
+            let size_mb: usize = value.parse()?;
+            search.set_table_size(size_mb.clamp(1, MAX_TABLE_SIZE_MB));
+        } else if name.eq_ignore_ascii_case("Clear Hash") {
+            search.clear_table();
+        } else if name.eq_ignore_ascii_case("Ponder") {
+            // Whether to ponder is up to the GUI, which tells with `go ponder`.
+        } else {
+            let (_, option) = SEARCH_OPTIONS
+                .iter()
+                .find(|(option, _)| name.eq_ignore_ascii_case(option))
+                .ok_or_else(|| anyhow!("Unknown option: {}", name))?;
+            let mut options = search.options();
+            *option(&mut options) = value.parse()?;
+            search.set_options(options);
+        }
+        Ok(())
+    }
+
+    /// Handles `position startpos|fen <fen> [moves <move>...]`.
+    fn position(&mut self, args: &[&str]) -> Result<()> {
+        let moves_start = args
+            .iter()
+            .position(|word| *word == "moves")
+            .unwrap_or(args.len());
+        let board = match args.split_first() {
+            Some((&"startpos", _)) => board_with_setup(),
+            Some((&"fen", _)) => Board::from_fen(args[1..moves_start].join(" "))
+                .map_err(|e| anyhow!("Invalid FEN: {:?}", e))?,
+            _ => {
+                return Err(anyhow!(
+                    "Usage: position startpos|fen <fen> [moves <move>...]"
+                ))
+            }
+        };
+
+        let mut game = Game::from_position(board);
+        for uci in args.get(moves_start + 1..).unwrap_or_default() {
+            let mv = Move::from_uci(uci)?.ok_or_else(|| anyhow!("Can't play a null move"))?;
+            game.play(&mv)?;
+        }
+        self.game = game;
+        Ok(())
+    }
+
+    /// Starts searching the current position, on its own thread.
+    fn go(&mut self, args: &[&str]) {
+        let mut go = parse_go(args, self.game.position().to_play());
+        self.stop();
+        let mut search = self.search.take().expect("no search is running");
+
+        if go.infinite {
+            go.limits = SearchLimits {
+                root_moves: go.limits.root_moves,
+                ..Default::default()
+            };
+        }
+
+        // The clock only starts once the opponent plays the move pondered on.
+        self.ponder.store(go.ponder, Ordering::Relaxed);
+
+        let held = go.ponder || go.infinite;
+        let (release, released) = mpsc::channel();
+        let game = self.game.clone();
+        let thread = thread::spawn(move || {
+            let result = search.run_game_with_progress(&game, &go.limits, send_info);
+            if held {
+                // Fails once the engine is gone, which also means it can stop waiting.
+                released.recv().ok();
+            }
+            send_best_move(&result);
+            search
+        });
+
+        self.thinking = Some(Thinking { thread, release });
+    }
+
+    /// Handles `ponderhit`: the opponent played the move pondered on, so the search goes on
+    /// as a normal one, with the time it was given.
+    fn ponderhit(&mut self) {
+        if let Some(thinking) = &self.thinking {
+            self.ponder.store(false, Ordering::Relaxed);
+            thinking.release.send(()).ok();
+        }
+    }
+
+    /// Stops the search in progress, if any, once it has sent its best move, and returns the
+    /// search.
+    fn stop(&mut self) -> &mut Search {
+        if let Some(thinking) = self.thinking.take() {
+            self.stop.store(true, Ordering::Relaxed);
+            thinking.release.send(()).ok();
+            let search = thinking.thread.join().expect("the search thread panicked");
+
+            // The search may have returned on its own before being told to stop.
+            self.stop.store(false, Ordering::Relaxed);
+            self.search = Some(search);
+        }
+        self.search
+            .as_mut()
+            .expect("the search is back once stopped")
+    }
+}
+
+/// Speaks UCI on the standard input and output until told to quit.
+pub fn run() -> Result<()> {
+    let mut engine = Engine::new();
+    for line in io::stdin().lock().lines() {
+        match engine.handle(&line?) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => println!("info string {}", e),
+        }
+    }
+
+    engine.stop();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> Option<Duration> {
+        Some(Duration::from_millis(millis))
+    }
+
+    #[test]
+    fn clock_of_the_side_to_play() {
+        let args = [
+            "wtime", "60000", "btime", "30000", "winc", "1000", "binc", "0",
+        ];
+        let white = parse_go(&args, Color::White);
+        assert_eq!(white.limits.time, millis(60_000 / 30 + 750));
+        let black = parse_go(&args, Color::Black);
+        assert_eq!(black.limits.time, millis(30_000 / 30));
+    }
+
+    #[test]
+    fn moves_to_go_split() {
+        let go = parse_go(&["wtime", "10000", "movestogo", "5"], Color::White);
+        assert_eq!(go.limits.time, millis(2_000));
+
+        // The last move before the time control gets it all, but for the overhead.
+        let go = parse_go(&["btime", "1000", "movestogo", "1"], Color::Black);
+        assert_eq!(
+            go.limits.time,
+            millis(1_000 - MOVE_OVERHEAD.as_millis() as u64)
+        );
+    }
+
+    #[test]
+    fn negative_clock() {
+        let go = parse_go(&["wtime", "-150", "winc", "100"], Color::White);
+        assert_eq!(go.limits.time, Some(Duration::default()));
+        assert_eq!(
+            allot_time(Duration::default(), millis(100).unwrap(), None),
+            Duration::default()
+        );
+    }
+
+    #[test]
+    fn explicit_limits() {
+        let go = parse_go(
+            &[
+                "wtime", "1000", "movetime", "250", "depth", "7", "nodes", "5000",
+            ],
+            Color::White,
+        );
+        assert_eq!(go.limits.time, millis(250));
+        assert_eq!(go.limits.depth, Some(7));
+        assert_eq!(go.limits.nodes, Some(5_000));
+        assert!(!go.infinite && !go.ponder);
+
+        let go = parse_go(&["ponder", "infinite"], Color::White);
+        assert!(go.infinite && go.ponder);
+        assert_eq!(go.limits, SearchLimits::default());
+    }
+
+    #[test]
+    fn search_moves_and_mate() {
+        let go = parse_go(&["searchmoves", "e2e4", "d2d4", "mate", "3"], Color::White);
+        let root_moves: Vec<_> = go.limits.root_moves.iter().map(Move::to_uci).collect();
+        assert_eq!(root_moves, vec!["e2e4", "d2d4"]);
+        assert_eq!(go.limits.depth, Some(5));
+    }
+
+    #[test]
+    fn unknown_go_parameters_are_skipped() {
+        let go = parse_go(
+            &["foo", "depth", "x", "bar", "12", "nodes", "100"],
+            Color::White,
+        );
+        assert_eq!(go.limits.depth, None);
+        assert_eq!(go.limits.nodes, Some(100));
+    }
+
+    #[test]
+    fn set_option() {
+        let mut engine = Engine::new();
+        engine
+            .set_option(&["name", "NullMovePruning", "value", "false"])
+            .unwrap();
+        engine
+            .set_option(&["name", "aspirationwindows", "value", "false"])
+            .unwrap();
+        let options = engine.stop().options();
+        assert!(!options.null_move_pruning && !options.aspiration_windows);
+        assert!(options.late_move_reductions);
+
+        engine.set_option(&["name", "Clear", "Hash"]).unwrap();
+        engine.set_option(&["name", "Hash", "value", "1"]).unwrap();
+        let err = engine
+            .set_option(&["name", "Move", "Overhead", "value", "10"])
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Unknown option: Move Overhead");
+        assert!(engine.set_option(&["Hash", "value", "1"]).is_err());
+        assert!(engine
+            .set_option(&["name", "CheckExtensions", "value", "maybe"])
+            .is_err());
+    }
+
+    #[test]
+    fn position() {
+        let mut engine = Engine::new();
+        engine
+            .position(&["startpos", "moves", "e2e4", "e7e5", "g1f3"])
+            .unwrap();
+        assert_eq!(
+            engine.game.position().to_fen(),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+
+        let fen = "4k3/8/8/8/8/8/8/R3K3 w Q - 0 1";
+        engine
+            .position(&["fen", "4k3/8/8/8/8/8/8/R3K3", "w", "Q", "-", "0", "1"])
+            .unwrap();
+        assert_eq!(engine.game.position().to_fen(), fen);
+
+        engine
+            .position(&[
+                "fen",
+                "4k3/8/8/8/8/8/8/R3K3",
+                "w",
+                "Q",
+                "-",
+                "0",
+                "1",
+                "moves",
+                "e1c1",
+            ])
+            .unwrap();
+        assert_eq!(engine.game.moves().count(), 1);
+
+        // A bad move leaves the position as it was.
+        assert!(engine
+            .position(&["startpos", "moves", "e2e4", "e2e4"])
+            .is_err());
+        assert!(engine.position(&["startpos", "moves", "e2"]).is_err());
+        assert_eq!(engine.game.moves().count(), 1);
+        assert!(engine.position(&["fen", "not", "a", "fen"]).is_err());
+        assert!(engine.position(&["somewhere"]).is_err());
+    }
+}
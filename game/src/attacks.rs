@@ -0,0 +1,296 @@
+//! Precomputed attack tables.
+//!
+//! Sliding pieces use "fancy" magic bitboards: the relevant blockers of a square are multiplied
+//! by a magic number so that their high bits index a table of precomputed attacks.
+
+use std::sync::OnceLock;
+
+use crate::bitboard::Bitboard;
+use crate::constants::BOARD_DIMENSION;
+use crate::prng::xorshift64_star;
+use crate::Color;
+
+const SQUARE_COUNT: usize = BOARD_DIMENSION * BOARD_DIMENSION;
+
+const KNIGHT_DELTAS: &[(i32, i32)] = &[
+    (1, 2),
+    (-1, 2),
+    (1, -2),
+    (-1, -2),
+    (2, 1),
+    (2, -1),
+    (-2, 1),
+    (-2, -1),
+];
+const KING_DELTAS: &[(i32, i32)] = &[
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+const ROOK_DIRECTIONS: &[(i32, i32)] = &[(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BISHOP_DIRECTIONS: &[(i32, i32)] = &[(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = FILE_A << 7;
+const ROW_1: u64 = 0xff;
+const ROW_8: u64 = ROW_1 << 56;
+
+/// Seeds for the magic number search, one per row. Any seed works, these ones find magics
+/// quickly.
+const MAGIC_SEEDS: [u64; BOARD_DIMENSION] = [728, 10316, 55013, 32803, 12281, 15100, 16645, 255];
+
+/// Returns the index of the square at an offset from another, if it is on the board.
+fn offset(index: usize, col_delta: i32, row_delta: i32) -> Option<usize> {
+    let col = (index % BOARD_DIMENSION) as i32 + col_delta;
+    let row = (index / BOARD_DIMENSION) as i32 + row_delta;
+    let dim = BOARD_DIMENSION as i32;
+    if col < 0 || col >= dim || row < 0 || row >= dim {
+        return None;
+    }
+    Some((row * dim + col) as usize)
+}
+
+fn step_attacks(index: usize, deltas: &[(i32, i32)]) -> Bitboard {
+    deltas
+        .iter()
+        .filter_map(|(col_delta, row_delta)| offset(index, *col_delta, *row_delta))
+        .fold(Bitboard::EMPTY, |bb, i| bb | Bitboard::from_index(i))
+}
+
+/// Computes sliding attacks by walking each ray until it hits a blocker (included).
+fn ray_attacks(index: usize, occupied: u64, directions: &[(i32, i32)]) -> u64 {
+    let mut attacks = 0;
+    for (col_delta, row_delta) in directions {
+        let mut current = index;
+        while let Some(next) = offset(current, *col_delta, *row_delta) {
+            attacks |= 1 << next;
+            if occupied & (1 << next) != 0 {
+                break;
+            }
+            current = next;
+        }
+    }
+    attacks
+}
+
+/// xorshift64* generator, used to look for magic numbers.
+struct Prng(u64);
+
+impl Prng {
+    fn next(&mut self) -> u64 {
+        let (state, number) = xorshift64_star(self.0);
+        self.0 = state;
+        number
+    }
+
+    /// Returns a number with few bits set, which make good magic candidates.
+    fn sparse(&mut self) -> u64 {
+        self.next() & self.next() & self.next()
+    }
+}
+
+struct Magic {
+    mask: u64,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupied: Bitboard) -> usize {
+        self.offset + ((occupied.0 & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+/// Finds magics for every square and fills the shared attack table.
+fn init_magics(directions: &[(i32, i32)], table: &mut Vec<Bitboard>) -> Vec<Magic> {
+    let mut magics = Vec::with_capacity(SQUARE_COUNT);
+
+    for index in 0..SQUARE_COUNT {
+        let row = index / BOARD_DIMENSION;
+        let col = index % BOARD_DIMENSION;
+
+        // Blockers on the edge of the board never change the attacks.
+        let edges =
+            ((ROW_1 | ROW_8) & !(ROW_1 << (8 * row))) | ((FILE_A | FILE_H) & !(FILE_A << col));
+        let mask = ray_attacks(index, 0, directions) & !edges;
+        let bits = mask.count_ones();
+
+        // Enumerate every subset of the mask (Carry-Rippler trick).
+        let mut occupancies = Vec::new();
+        let mut references = Vec::new();
+        let mut subset: u64 = 0;
+        loop {
+            occupancies.push(subset);
+            references.push(ray_attacks(index, subset, directions));
+            subset = subset.wrapping_sub(mask) & mask;
+            if subset == 0 {
+                break;
+            }
+        }
+
+        let shift = 64 - bits;
+        let size = 1 << bits;
+        let mut prng = Prng(MAGIC_SEEDS[row]);
+        let mut attempt = vec![0; size];
+
+        // Entries filled during an earlier attempt are told apart by their epoch, which saves
+        // clearing the table between attempts.
+        let mut epochs = vec![0; size];
+        let mut epoch = 0;
+
+        let magic = loop {
+            let magic = prng.sparse();
+            if (mask.wrapping_mul(magic) >> 56).count_ones() < 6 {
+                continue;
+            }
+
+            epoch += 1;
+            let collides = occupancies
+                .iter()
+                .zip(references.iter())
+                .any(|(occ, reference)| {
+                    let i = (occ.wrapping_mul(magic) >> shift) as usize;
+                    if epochs[i] < epoch {
+                        epochs[i] = epoch;
+                        attempt[i] = *reference;
+                        false
+                    } else {
+                        attempt[i] != *reference
+                    }
+                });
+
+            if !collides {
+                break magic;
+            }
+        };
+
+        let offset = table.len();
+        table.extend(attempt.into_iter().map(Bitboard));
+        magics.push(Magic {
+            mask,
+            magic,
+            shift,
+            offset,
+        });
+    }
+
+    magics
+}
+
+struct AttackTables {
+    knight: Vec<Bitboard>,
+    king: Vec<Bitboard>,
+    /// Pawn captures, indexed by color then square.
+    pawn: [Vec<Bitboard>; 2],
+    rook_magics: Vec<Magic>,
+    bishop_magics: Vec<Magic>,
+    sliding: Vec<Bitboard>,
+    /// Squares strictly between two aligned squares, indexed by `a * 64 + b`.
+    between: Vec<Bitboard>,
+    /// Full line through two aligned squares, indexed by `a * 64 + b`.
+    line: Vec<Bitboard>,
+}
+
+impl AttackTables {
+    fn new() -> Self {
+        let knight = (0..SQUARE_COUNT)
+            .map(|i| step_attacks(i, KNIGHT_DELTAS))
+            .collect();
+        let king = (0..SQUARE_COUNT)
+            .map(|i| step_attacks(i, KING_DELTAS))
+            .collect();
+        let pawn = [
+            (0..SQUARE_COUNT)
+                .map(|i| step_attacks(i, &[(1, -1), (-1, -1)]))
+                .collect(),
+            (0..SQUARE_COUNT)
+                .map(|i| step_attacks(i, &[(1, 1), (-1, 1)]))
+                .collect(),
+        ];
+
+        let mut sliding = Vec::new();
+        let rook_magics = init_magics(ROOK_DIRECTIONS, &mut sliding);
+        let bishop_magics = init_magics(BISHOP_DIRECTIONS, &mut sliding);
+
+        let mut between = vec![Bitboard::EMPTY; SQUARE_COUNT * SQUARE_COUNT];
+        let mut line = vec![Bitboard::EMPTY; SQUARE_COUNT * SQUARE_COUNT];
+        for a in 0..SQUARE_COUNT {
+            for directions in &[ROOK_DIRECTIONS, BISHOP_DIRECTIONS] {
+                let from_a = ray_attacks(a, 0, directions);
+                for b in Bitboard(from_a) {
+                    let from_b = ray_attacks(b, 0, directions);
+                    let bb_a = 1 << a;
+                    let bb_b = 1 << b;
+                    between[a * SQUARE_COUNT + b] = Bitboard(
+                        ray_attacks(a, bb_b, directions) & ray_attacks(b, bb_a, directions),
+                    );
+                    line[a * SQUARE_COUNT + b] = Bitboard((from_a & from_b) | bb_a | bb_b);
+                }
+            }
+        }
+
+        Self {
+            knight,
+            king,
+            pawn,
+            rook_magics,
+            bishop_magics,
+            sliding,
+            between,
+            line,
+        }
+    }
+}
+
+static TABLES: OnceLock<AttackTables> = OnceLock::new();
+
+fn tables() -> &'static AttackTables {
+    TABLES.get_or_init(AttackTables::new)
+}
+
+pub(crate) fn knight_attacks(index: usize) -> Bitboard {
+    tables().knight[index]
+}
+
+pub(crate) fn king_attacks(index: usize) -> Bitboard {
+    tables().king[index]
+}
+
+/// Squares attacked by a pawn of a given color.
+pub(crate) fn pawn_attacks(color: Color, index: usize) -> Bitboard {
+    let color_index = match color {
+        Color::Black => 0,
+        Color::White => 1,
+    };
+    tables().pawn[color_index][index]
+}
+
+pub(crate) fn rook_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+    let t = tables();
+    t.sliding[t.rook_magics[index].index(occupied)]
+}
+
+pub(crate) fn bishop_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+    let t = tables();
+    t.sliding[t.bishop_magics[index].index(occupied)]
+}
+
+pub(crate) fn queen_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+    rook_attacks(index, occupied) | bishop_attacks(index, occupied)
+}
+
+/// Squares strictly between two squares on the same line, or nothing if they aren't aligned.
+pub(crate) fn between(a: usize, b: usize) -> Bitboard {
+    tables().between[a * SQUARE_COUNT + b]
+}
+
+/// The whole line going through two squares, or nothing if they aren't aligned.
+pub(crate) fn line(a: usize, b: usize) -> Bitboard {
+    tables().line[a * SQUARE_COUNT + b]
+}
@@ -1,7 +1,10 @@
-use snafu::{ensure, ResultExt, Snafu};
+use snafu::{ensure, OptionExt, Snafu};
 
-use crate::constants::BOARD_DIMENSION;
-use crate::{Color, Column, NotationError, Piece, PieceType, Square};
+use crate::attacks;
+use crate::bitboard::Bitboard;
+use crate::constants::*;
+use crate::zobrist;
+use crate::{Color, Column, Move, MoveKind, NotationError, Piece, PieceType, Row, Square};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CastleState {
@@ -11,6 +14,64 @@ pub enum CastleState {
     None,
 }
 
+impl CastleState {
+    /// Whether castling on a given side is allowed.
+    pub(crate) fn allows(self, side: CastleState) -> bool {
+        self == CastleState::Both || self == side
+    }
+
+    /// Adds the right to castle on a given side.
+    pub(crate) fn with(self, side: CastleState) -> CastleState {
+        match (self, side) {
+            (CastleState::None, side) => side,
+            (state, side) if state == side => state,
+            _ => CastleState::Both,
+        }
+    }
+
+    /// Removes the right to castle on a given side.
+    pub(crate) fn without(self, side: CastleState) -> CastleState {
+        match (self, side) {
+            (CastleState::Both, CastleState::Kingside) => CastleState::Queenside,
+            (CastleState::Both, CastleState::Queenside) => CastleState::Kingside,
+            (state, side) if state != side && side != CastleState::Both => state,
+            _ => CastleState::None,
+        }
+    }
+}
+
+/// The squares the king and rook move from and to when castling.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Castling {
+    pub king_src: usize,
+    pub king_dst: usize,
+    pub rook_src: usize,
+    pub rook_dst: usize,
+}
+
+impl Castling {
+    /// The destination a castling move is written with: the king's own when the king and rook
+    /// start on their classical squares, and the rook's square otherwise, as in Chess960. Moving
+    /// the king onto its rook stays unambiguous whatever files they start on.
+    pub(crate) fn move_dst(&self) -> usize {
+        let king_col = Square::from_index(self.king_src).col();
+        let rook_col = Square::from_index(self.rook_src).col();
+        if king_col == Column::E && (rook_col == Column::A || rook_col == Column::H) {
+            self.king_dst
+        } else {
+            self.rook_src
+        }
+    }
+}
+
+/// The row a side's pieces start on.
+pub(crate) fn home_row(color: Color) -> Row {
+    match color {
+        Color::White => WHITE_PIECES_ROW,
+        Color::Black => BLACK_PIECES_ROW,
+    }
+}
+
 #[derive(Debug, Snafu, PartialEq)]
 #[snafu(visibility(pub(crate)))]
 pub enum MoveError {
@@ -40,313 +101,366 @@ pub enum MoveError {
 
     #[snafu(display("Not your turn to play."))]
     WrongPlayer,
+
+    #[snafu(display("A pawn reaching the last rank must be promoted."))]
+    PromotionRequired,
+
+    #[snafu(display("Invalid promotion."))]
+    InvalidPromotion,
+}
+
+impl MoveError {
+    /// A stable, machine-readable code for the error, unlike its message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            MoveError::NoPieceToMove => "no_piece_to_move",
+            MoveError::InvalidMove => "invalid_move",
+            MoveError::DestinationObstructed => "destination_obstructed",
+            MoveError::PathObstructed => "path_obstructed",
+            MoveError::PutSelfInCheck => "put_self_in_check",
+            MoveError::InvalidSquare { .. } => "invalid_square",
+            MoveError::CannotCastle => "cannot_castle",
+            MoveError::CannotCastleThroughCheck => "cannot_castle_through_check",
+            MoveError::WrongPlayer => "wrong_player",
+            MoveError::PromotionRequired => "promotion_required",
+            MoveError::InvalidPromotion => "invalid_promotion",
+        }
+    }
 }
 
 type Result<T> = std::result::Result<T, MoveError>;
 
+/// Number of squares on the board.
+const SQUARE_COUNT: usize = BOARD_DIMENSION * BOARD_DIMENSION;
+
 #[derive(Clone, Debug)]
 pub struct Board {
-    pub board: [[Option<Piece>; BOARD_DIMENSION]; BOARD_DIMENSION],
+    /// The piece on each square, indexed by square index. Kept in sync with the bitboards so
+    /// looking up a single square stays cheap.
+    squares: [Option<Piece>; SQUARE_COUNT],
+
+    /// The squares occupied by each type of piece, indexed by `PieceType::index`.
+    pub(crate) pieces: [Bitboard; 6],
+
+    /// The squares occupied by each side, indexed by `Color::index`.
+    pub(crate) colors: [Bitboard; 2],
 
     pub(crate) to_play: Color,
     pub(crate) full_move_clock: usize,
     pub(crate) half_move_clock: usize,
 
-    pub(crate) black_king_position: Square,
-    pub(crate) white_king_position: Square,
+    /// The castling rights of each side, indexed by `Color::index`.
+    pub(crate) castle_states: [CastleState; 2],
+
+    /// The file of the rook each side castles with, indexed by `Color::index` then kingside and
+    /// queenside. Only Chess960 starts the rooks off the A and H files.
+    pub(crate) castling_rook_cols: [[Column; 2]; 2],
 
+    /// The square a pawn moves to when capturing en passant, behind the pawn that just moved
+    /// two squares.
     pub(crate) en_passant_square: Option<Square>,
+
+    /// Zobrist hash of the position, kept up to date as pieces and state change.
+    pub(crate) hash: u64,
 }
 
 impl Default for Board {
     fn default() -> Self {
         Self {
-            board: Default::default(),
+            squares: [None; SQUARE_COUNT],
+            pieces: Default::default(),
+            colors: Default::default(),
             to_play: Color::White,
             full_move_clock: 1,
             half_move_clock: 0,
-            black_king_position: Square::new(Column::E, 7),
-            white_king_position: Square::new(Column::E, 0),
+            castle_states: [CastleState::None; 2],
+            castling_rook_cols: [[Column::H, Column::A]; 2],
             en_passant_square: None,
+            hash: 0,
         }
     }
 }
 
 impl Board {
+    /// The color whose turn it is.
+    pub fn to_play(&self) -> Color {
+        self.to_play
+    }
+
+    /// A 64-bit Zobrist hash of the position.
+    ///
+    /// It covers piece placement, the side to play, castling rights and the en-passant file when
+    /// a capture is possible, but not the move clocks. Hashes are stable across runs and builds.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Recomputes the hash from scratch, after the position was set up field by field.
+    pub(crate) fn reset_hash(&mut self) {
+        self.hash = self.compute_hash();
+    }
+
     pub fn at(&self, square: &Square) -> Option<&Piece> {
-        self.board[usize::from(square.col)][square.row].as_ref()
+        self.squares[square.index()].as_ref()
     }
 
-    pub(crate) fn at_mut(&mut self, square: &Square) -> &mut Option<Piece> {
-        &mut self.board[usize::from(square.col)][square.row]
+    /// Returns the piece on the square with a given index.
+    pub(crate) fn piece_at(&self, index: usize) -> Option<Piece> {
+        self.squares[index]
     }
 
     /// Clear all pieces from the board.
     pub fn clear(&mut self) {
-        self.board = Default::default();
+        self.squares = [None; SQUARE_COUNT];
+        self.pieces = Default::default();
+        self.colors = Default::default();
+        self.reset_hash();
     }
 
     /// Place a piece on an empty square of the board.
     pub fn place_piece(&mut self, square: &Square, piece: Piece) {
-        let piece_maybe = self.at_mut(square);
-        assert!(piece_maybe.is_none());
-        piece_maybe.replace(piece);
+        assert!(self.at(square).is_none());
+        self.put_piece(square.index(), piece);
+    }
+
+    pub(crate) fn put_piece(&mut self, index: usize, piece: Piece) {
+        let bb = Bitboard::from_index(index);
+        self.squares[index] = Some(piece);
+        self.hash ^= zobrist::piece_key(piece, index);
+        self.pieces[piece.piece_type.index()] |= bb;
+        self.colors[piece.color.index()] |= bb;
+    }
+
+    pub(crate) fn remove_piece(&mut self, index: usize) -> Option<Piece> {
+        let piece = self.squares[index].take()?;
+        self.hash ^= zobrist::piece_key(piece, index);
+        let bb = !Bitboard::from_index(index);
+        self.pieces[piece.piece_type.index()] &= bb;
+        self.colors[piece.color.index()] &= bb;
+        Some(piece)
+    }
+
+    /// The squares holding a piece of either side.
+    pub(crate) fn occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
+
+    /// The squares holding pieces of a given type and color.
+    pub(crate) fn pieces_of(&self, piece_type: PieceType, color: Color) -> Bitboard {
+        self.pieces[piece_type.index()] & self.colors[color.index()]
+    }
+
+    pub(crate) fn king_index(&self, color: Color) -> Option<usize> {
+        self.pieces_of(PieceType::King, color).first()
     }
 
     pub(crate) fn get_castle_state(&self, color: Color) -> CastleState {
-        let king_pos = match color {
-            Color::Black => &self.black_king_position,
-            Color::White => &self.white_king_position,
-        };
+        self.castle_states[color.index()]
+    }
 
-        let king = self.at(king_pos).unwrap();
-        if king.moved_once {
-            return CastleState::None;
-        }
+    pub(crate) fn set_castle_state(&mut self, color: Color, state: CastleState) {
+        let old_state = self.castle_states[color.index()];
+        self.hash ^= zobrist::castle_key(color, old_state) ^ zobrist::castle_key(color, state);
+        self.castle_states[color.index()] = state;
+    }
 
-        let mut castle_state = CastleState::None;
-        for (rook_square, partial_state) in &[
-            (Square::new(Column::H, king_pos.row), CastleState::Kingside),
-            (Square::new(Column::A, king_pos.row), CastleState::Queenside),
-        ] {
-            let rook_maybe = self.at(rook_square);
-            if rook_maybe.is_none() {
-                continue;
-            }
-            if rook_maybe.unwrap().moved_once {
-                continue;
-            }
+    /// Returns the square of the rook a side castles with on a given side.
+    pub(crate) fn castling_rook(&self, color: Color, side: CastleState) -> usize {
+        let [kingside, queenside] = self.castling_rook_cols[color.index()];
+        let col = if side == CastleState::Kingside {
+            kingside
+        } else {
+            queenside
+        };
+        Square::new(col, home_row(color)).index()
+    }
 
-            if castle_state == CastleState::None {
-                castle_state = *partial_state;
-            } else {
-                castle_state = CastleState::Both;
+    /// Returns the side and castling direction of the rook starting on a given square, if any.
+    pub(crate) fn castling_rook_side(&self, index: usize) -> Option<(Color, CastleState)> {
+        for color in &[Color::White, Color::Black] {
+            for side in &[CastleState::Kingside, CastleState::Queenside] {
+                if self.castling_rook(*color, *side) == index {
+                    return Some((*color, *side));
+                }
             }
         }
+        None
+    }
+
+    /// Returns the squares the king and rook move from and to when a king on a given square
+    /// castles. They end up on the same squares as in classical chess whatever their start.
+    pub(crate) fn castling(&self, color: Color, side: CastleState, king_src: usize) -> Castling {
+        let row = home_row(color);
+        let (king_dst, rook_dst) = if side == CastleState::Kingside {
+            (Column::G, Column::F)
+        } else {
+            (Column::C, Column::D)
+        };
+        Castling {
+            king_src,
+            king_dst: Square::new(king_dst, row).index(),
+            rook_src: self.castling_rook(color, side),
+            rook_dst: Square::new(rook_dst, row).index(),
+        }
+    }
 
-        castle_state
-    }
-
-    pub(crate) fn validate_square_threatened(&self, square: &Square, by_color: Color) -> bool {
-        println!("Checking for threats to {} by {:?}", square, by_color);
-
-        // Check for threats by knights.
-        for (col_move, row_move) in &[
-            (1, 2),
-            (-1, 2),
-            (1, -2),
-            (-1, -2),
-            (2, 1),
-            (2, -1),
-            (-2, 1),
-            (-2, -1),
-        ] {
-            if let Some(attacker) = square.clone().relative(*col_move, *row_move) {
-                if let Some(atk_piece) = self.at(&attacker) {
-                    if atk_piece.piece_type == PieceType::Knight && atk_piece.color == by_color {
-                        println!(
-                            "{} threatened by {:?} at {}",
-                            square, atk_piece.piece_type, attacker,
-                        );
-                        return true;
-                    }
-                }
-            }
+    /// Returns the side a king move castles towards, if it is written as a castling move: two
+    /// squares sideways from the classical squares, or onto one of its castling rooks otherwise.
+    pub(crate) fn castling_side(&self, src: &Square, dst: &Square) -> Option<CastleState> {
+        let king = self.at(src)?;
+        if king.piece_type != PieceType::King || src.row() != dst.row() {
+            return None;
         }
 
-        // Check for lateral & diagonal threats.
-        for col_delta in &[0, 1, -1] {
-            for row_delta in &[0, 1, -1] {
-                if *row_delta == 0 && *col_delta == 0 {
-                    // Prevent an infinite loop.
-                    continue;
-                }
+        let side = match self.castling_rook_side(dst.index()) {
+            Some((color, side)) if color == king.color => side,
+            _ if dst.col() > src.col() => CastleState::Kingside,
+            _ => CastleState::Queenside,
+        };
+        // Moving onto the rook's square is only castling while the rook is still there.
+        let castling = self.castling(king.color, side, src.index());
+        let rook = Piece::new(PieceType::Rook, king.color);
+        if castling.move_dst() == dst.index()
+            && (dst.index() != castling.rook_src || self.at(dst) == Some(&rook))
+        {
+            Some(side)
+        } else {
+            None
+        }
+    }
 
-                let is_diagonal = (*col_delta as i32).abs() > 0 && (*row_delta as i32).abs() > 0;
-
-                let mut lat_square_pos = square.clone();
-                while let Some(s) = lat_square_pos.relative(*col_delta, *row_delta) {
-                    if let Some(atk_piece) = self.at(&s) {
-                        if atk_piece.color != by_color {
-                            // Line of sight is blocked by friendly piece.
-                            break;
-                        } else {
-                            if is_diagonal {
-                                if atk_piece.piece_type == PieceType::Bishop
-                                    || atk_piece.piece_type == PieceType::Queen
-                                {
-                                    return true;
-                                }
-
-                                if atk_piece.piece_type == PieceType::Pawn
-                                    && (usize::from(square.col) as i16 - usize::from(s.col) as i16)
-                                        .abs()
-                                        == 1
-                                    && (square.row as i16 - s.row as i16).abs() == 1
-                                {
-                                    println!("THREATENED HERE");
-                                    return true;
-                                }
-                            }
-
-                            if !is_diagonal
-                                && (atk_piece.piece_type == PieceType::Rook
-                                    || atk_piece.piece_type == PieceType::Queen)
-                            {
-                                return true;
-                            }
-                        }
-                    }
-                    lat_square_pos = s;
-                }
-            }
+    pub(crate) fn set_en_passant_square(&mut self, square: Option<Square>) {
+        if let Some(old_square) = &self.en_passant_square {
+            self.hash ^= self.en_passant_key(old_square);
+        }
+        if let Some(new_square) = &square {
+            self.hash ^= self.en_passant_key(new_square);
         }
+        self.en_passant_square = square;
+    }
 
-        false
+    /// Returns the pieces of both sides attacking a square, given the squares occupied on the
+    /// board.
+    pub(crate) fn attackers_to(&self, index: usize, occupied: Bitboard) -> Bitboard {
+        let bishops =
+            self.pieces[PieceType::Bishop.index()] | self.pieces[PieceType::Queen.index()];
+        let rooks = self.pieces[PieceType::Rook.index()] | self.pieces[PieceType::Queen.index()];
+
+        (attacks::pawn_attacks(Color::White, index) & self.pieces_of(PieceType::Pawn, Color::Black))
+            | (attacks::pawn_attacks(Color::Black, index)
+                & self.pieces_of(PieceType::Pawn, Color::White))
+            | (attacks::knight_attacks(index) & self.pieces[PieceType::Knight.index()])
+            | (attacks::king_attacks(index) & self.pieces[PieceType::King.index()])
+            | (attacks::bishop_attacks(index, occupied) & bishops)
+            | (attacks::rook_attacks(index, occupied) & rooks)
     }
 
-    fn validate_check(&self, color: Color) -> bool {
-        match color {
-            Color::Black => {
-                self.validate_square_threatened(&self.black_king_position, Color::White)
-            }
-            Color::White => {
-                self.validate_square_threatened(&self.white_king_position, Color::Black)
-            }
+    pub(crate) fn validate_square_threatened(&self, index: usize, by_color: Color) -> bool {
+        !(self.attackers_to(index, self.occupied()) & self.colors[by_color.index()]).is_empty()
+    }
+
+    pub(crate) fn validate_check(&self, color: Color) -> bool {
+        match self.king_index(color) {
+            Some(king) => self.validate_square_threatened(king, color.opposite()),
+            None => false,
         }
     }
 
     /// Validates that a move is legal.
-    pub fn validate_move(&self, src: &Square, dst: &Square) -> Result<()> {
-        // Validate that we have a piece to move.
-        let piece_maybe = self.at(src);
-        ensure!(piece_maybe.is_some(), NoPieceToMove);
+    ///
+    /// The promotion piece is required for a pawn reaching the last rank, and must be `None` for
+    /// any other move.
+    ///
+    /// Returns the move with its details filled in.
+    pub fn validate_move(&self, mv: &Move) -> Result<Move> {
+        let src = &mv.src;
+        let dst = &mv.dst;
 
-        let piece = piece_maybe.unwrap();
+        // Validate that we have a piece to move.
+        let piece = self.at(src).context(NoPieceToMove)?;
 
         // Validate that the destination square is available.
+        let castle_side = self.castling_side(src, dst);
         if let Some(dst_piece) = self.at(dst) {
-            // If a capture, make sure we only capture the other side.
-            ensure!(piece.color != dst_piece.color, DestinationObstructed);
+            // If a capture, make sure we only capture the other side. A king castles by moving
+            // onto its own rook in Chess960.
+            ensure!(
+                piece.color != dst_piece.color || castle_side.is_some(),
+                DestinationObstructed
+            );
         }
 
+        // Only pawns can be promoted.
+        ensure!(
+            mv.promotion.is_none() || piece.piece_type == PieceType::Pawn,
+            InvalidPromotion
+        );
+
         // Piece-specific logic.
         match &piece.piece_type {
             PieceType::Knight => self.validate_knight(src, dst)?,
-            PieceType::Pawn => self.validate_pawn(src, dst, piece)?,
+            PieceType::Pawn => self.validate_pawn(src, dst, piece, mv.promotion)?,
             PieceType::Rook => self.validate_rook(src, dst)?,
             PieceType::King => self.validate_king(src, dst)?,
             PieceType::Bishop => self.validate_bishop(src, dst)?,
             PieceType::Queen => self.validate_queen(src, dst)?,
         }
 
-        Ok(())
-    }
+        let coll_diff = usize::from(dst.col()) as i32 - usize::from(src.col()) as i32;
+        let row_diff_abs = (dst.row() as i32 - src.row() as i32).abs();
+        let mut captured = self.at(dst).map(|p| p.piece_type);
 
-    /// Move a piece from one square to another, respecting the rules of the game.
-    ///
-    /// Returns the next position, or an error if the move was invalid.
-    pub fn move_piece(&self, src: &Square, dst: &Square) -> Result<Board> {
-        self.validate_move(src, dst)?;
-
-        let mut new_position = self.clone();
-        new_position.to_play = self.to_play.opposite();
-
-        let piece = new_position.at_mut(src).take().unwrap(); // Unwrap safe because validate move throws.
-
-        ensure!(piece.color == self.to_play, WrongPlayer);
-
-        if piece.color == Color::Black {
-            new_position.full_move_clock += 1;
-        }
-
-        let coll_diff = usize::from(dst.col) as i32 - usize::from(src.col) as i32;
-
-        let captured_maybe = {
-            let en_passant = new_position.at(dst).is_none()
-                && piece.piece_type == PieceType::Pawn
-                && coll_diff != 0;
-
-            let mut captured = new_position.at_mut(dst).replace(piece.clone());
-
-            if en_passant {
-                assert!(new_position.en_passant_square.is_some());
-                let captured_pawn = new_position
-                    .at_mut(&new_position.en_passant_square.clone().unwrap())
-                    .take()
-                    .unwrap();
-                captured.replace(captured_pawn);
+        let kind = match (piece.piece_type, castle_side) {
+            (PieceType::King, Some(side)) => {
+                captured = None;
+                if side == CastleState::Kingside {
+                    MoveKind::KingsideCastle
+                } else {
+                    MoveKind::QueensideCastle
+                }
             }
-
-            captured
+            (PieceType::Pawn, _) if coll_diff != 0 && captured.is_none() => {
+                captured = Some(PieceType::Pawn);
+                MoveKind::EnPassant
+            }
+            (PieceType::Pawn, _) if row_diff_abs == 2 => MoveKind::DoublePawnPush,
+            _ => MoveKind::Normal,
         };
-        // TODO: Do something with the captured piece.
-
-        if piece.piece_type == PieceType::Pawn || captured_maybe.is_some() {
-            new_position.half_move_clock = 0;
-        } else {
-            new_position.half_move_clock += 1;
-        }
 
-        // Reset en-passant
-        if self.en_passant_square.is_some() {
-            new_position.en_passant_square = None;
-        }
+        Ok(Move {
+            piece: Some(piece.piece_type),
+            captured,
+            kind,
+            ..mv.clone()
+        })
+    }
 
-        match &piece.piece_type {
-            PieceType::King => {
-                // Update the king position so we can make sure it's not in check.
-                match &piece.color {
-                    Color::Black => {
-                        new_position.black_king_position = dst.clone();
-                    }
-                    Color::White => new_position.white_king_position = dst.clone(),
-                }
-            }
-            PieceType::Pawn => {
-                let row_diff_abs = ((src.row as i16) - (dst.row as i16)).abs();
-                if row_diff_abs == 2 {
-                    new_position.en_passant_square = Some(dst.clone());
-                }
-            }
-            _ => {}
-        }
+    /// Move a piece from one square to another, respecting the rules of the game.
+    ///
+    /// Returns the next position along with the move played, or an error if the move was
+    /// invalid.
+    pub fn move_piece(&self, mv: &Move) -> Result<(Board, Move)> {
+        let mv = self.validate_move(mv)?;
 
-        ensure!(!new_position.validate_check(piece.color), PutSelfInCheck);
-
-        // Mark this piece as moved (used for tracking castling.)
-        new_position.at_mut(dst).as_mut().unwrap().moved_once = true;
-
-        if piece.piece_type == PieceType::King && coll_diff.abs() == 2 {
-            // If we reached here we just castled.
-            // We need to move the corresponding rook as well.
-            let (rook_src, rook_dst) = {
-                if coll_diff > 0 {
-                    // Kingside castling.
-                    (
-                        Square::new(Column::H, src.row),
-                        Square::new(Column::F, src.row),
-                    )
-                } else {
-                    // Queenside castling
-                    (
-                        Square::new(Column::A, src.row),
-                        Square::new(Column::D, src.row),
-                    )
-                }
-            };
+        // Unwrap safe because validate move throws.
+        let color = self.at(&mv.src).unwrap().color;
+        ensure!(color == self.to_play, WrongPlayer);
 
-            // Move the rook.
-            let rook = new_position.at_mut(&rook_src).take().unwrap();
-            new_position.at_mut(&rook_dst).replace(rook);
-        }
+        let mut new_position = self.clone();
+        new_position.make_move(mv.clone());
+        ensure!(!new_position.validate_check(color), PutSelfInCheck);
 
-        Ok(new_position)
+        Ok((new_position, mv))
     }
 
-    pub fn move_notation(&self, src: &str, dst: &str) -> Result<Board> {
-        let src_square = Square::from_notation(src).context(InvalidSquare)?;
-        let dst_square = Square::from_notation(dst).context(InvalidSquare)?;
-        self.move_piece(&src_square, &dst_square)
+    /// Move a piece using square notation (e.g. "e7" "e8"), with an optional promotion piece
+    /// (e.g. 'q' or 'n').
+    pub fn move_notation(
+        &self,
+        src: &str,
+        dst: &str,
+        promotion: Option<char>,
+    ) -> Result<(Board, Move)> {
+        self.move_piece(&Move::from_notation(src, dst, promotion)?)
     }
 }
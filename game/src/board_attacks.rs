@@ -0,0 +1,100 @@
+use crate::attacks;
+use crate::bitboard::Bitboard;
+use crate::{Board, Color, PieceType, Square};
+
+/// A piece that cannot leave the line between its king and an enemy slider without exposing the
+/// king.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pin {
+    /// The square of the pinned piece.
+    pub pinned: Square,
+
+    /// The square of the piece pinning it.
+    pub pinner: Square,
+
+    /// The squares between the king and the pinning piece, the pinning piece included. The pinned
+    /// piece can only move along these squares.
+    pub ray: Vec<Square>,
+}
+
+fn to_squares(bb: Bitboard) -> Vec<Square> {
+    bb.map(Square::from_index).collect()
+}
+
+impl Board {
+    /// Whether a square is attacked by any piece of a given color.
+    ///
+    /// The square doesn't need to be empty, and a piece defending one of its own is counted as
+    /// attacking its square.
+    pub fn is_square_attacked(&self, square: &Square, by_color: Color) -> bool {
+        self.validate_square_threatened(square.index(), by_color)
+    }
+
+    /// Returns the squares of the pieces of a given color attacking a square.
+    pub fn attackers_of(&self, square: &Square, color: Color) -> Vec<Square> {
+        to_squares(self.attackers_to(square.index(), self.occupied()) & self.colors[color.index()])
+    }
+
+    /// Returns the squares of the pieces giving check to the side to play.
+    pub fn checkers(&self) -> Vec<Square> {
+        to_squares(self.checkers_bitboard())
+    }
+
+    /// Returns the pieces of a given color pinned to their king.
+    pub fn pinned_pieces(&self, color: Color) -> Vec<Pin> {
+        let king = match self.king_index(color) {
+            Some(king) => king,
+            None => return Vec::new(),
+        };
+
+        self.pins(color)
+            .into_iter()
+            .map(|(pinned, pinner)| Pin {
+                pinned: Square::from_index(pinned),
+                pinner: Square::from_index(pinner),
+                ray: to_squares(attacks::between(king, pinner) | Bitboard::from_index(pinner)),
+            })
+            .collect()
+    }
+
+    pub(crate) fn checkers_bitboard(&self) -> Bitboard {
+        let color = self.to_play;
+        match self.king_index(color) {
+            Some(king) => {
+                self.attackers_to(king, self.occupied()) & self.colors[color.opposite().index()]
+            }
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// Returns the squares of the pieces of a given color pinned to their king, along with the
+    /// square of the piece pinning them.
+    pub(crate) fn pins(&self, color: Color) -> Vec<(usize, usize)> {
+        let king = match self.king_index(color) {
+            Some(king) => king,
+            None => return Vec::new(),
+        };
+
+        let enemy = color.opposite();
+        let queens = self.pieces_of(PieceType::Queen, enemy);
+        let rooks = self.pieces_of(PieceType::Rook, enemy) | queens;
+        let bishops = self.pieces_of(PieceType::Bishop, enemy) | queens;
+
+        // Enemy sliders that would attack the king on an empty board.
+        let snipers = (attacks::rook_attacks(king, Bitboard::EMPTY) & rooks)
+            | (attacks::bishop_attacks(king, Bitboard::EMPTY) & bishops);
+
+        let occupied = self.occupied();
+        let own = self.colors[color.index()];
+        snipers
+            .filter_map(|sniper| {
+                let blockers = attacks::between(king, sniper) & occupied;
+                if blockers.count() == 1 && !(blockers & own).is_empty() {
+                    blockers.first().map(|pinned| (pinned, sniper))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
@@ -3,8 +3,11 @@ use std::{convert::TryFrom, num::ParseIntError};
 use snafu::{ensure, ResultExt, Snafu};
 
 use crate::{
-    board::CastleState, constants::BOARD_DIMENSION, pieces::PieceError,
-    square::Error as SquareError, Board, Color, Column, Piece, PieceType, Square,
+    board::{home_row, CastleState},
+    constants::*,
+    pieces::PieceError,
+    square::Error as SquareError,
+    Board, Color, Column, Piece, PieceType, Square,
 };
 
 #[derive(Debug, Snafu)]
@@ -14,35 +17,81 @@ pub enum FenError {
     InvalidColCount,
     TooManyRows,
     TooManyCols,
-    InvalidPiece { source: PieceError },
-    InvalidColorToPlay { source: PieceError },
-    InvalidEnPassant { source: SquareError },
-    InvalidHalfMoveClock { source: ParseIntError },
-    InvalidFullMoveClock { source: ParseIntError },
+    InvalidCastlingField,
+    InvalidPiece {
+        source: PieceError,
+    },
+    InvalidColorToPlay {
+        source: PieceError,
+    },
+    InvalidEnPassant {
+        source: SquareError,
+    },
+    InvalidEnPassantRank,
+    InvalidHalfMoveClock {
+        source: ParseIntError,
+    },
+    InvalidFullMoveClock {
+        source: ParseIntError,
+    },
+
+    #[snafu(display("The {:?} king is missing.", color))]
+    MissingKing {
+        color: Color,
+    },
+
+    #[snafu(display("There is more than one {:?} king.", color))]
+    TooManyKings {
+        color: Color,
+    },
+
+    #[snafu(display("{:?} has more than 8 pawns.", color))]
+    TooManyPawns {
+        color: Color,
+    },
+
+    #[snafu(display("{:?} has more than 16 pieces.", color))]
+    TooManyPieces {
+        color: Color,
+    },
+
+    #[snafu(display("There is a pawn on the first or last rank, on {}.", square))]
+    PawnOnBackRank {
+        square: Square,
+    },
+
+    #[snafu(display("The side that just moved is in check."))]
+    OpponentInCheck,
+
+    #[snafu(display("The king is in check by more than two pieces."))]
+    TooManyCheckers,
+
+    #[snafu(display(
+        "{:?} can't castle without its king and rook on their starting squares.",
+        color
+    ))]
+    InvalidCastlingRights {
+        color: Color,
+    },
+
+    #[snafu(display("No pawn just moved two squares past the en-passant square."))]
+    EnPassantWithoutPawn,
 }
 
 type Result<T> = std::result::Result<T, FenError>;
 
-impl CastleState {
-    fn to_fen(&self, color: Color) -> String {
-        let s = match self {
-            CastleState::Both => "kq",
-            CastleState::Kingside => "k",
-            CastleState::Queenside => "q",
-            CastleState::None => "",
-        }
-        .to_string();
+/// How much checking FEN parsing does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FenMode {
+    /// Only check the FEN syntax, accepting positions that cannot arise in a game.
+    Lenient,
 
-        if color == Color::White {
-            s.to_uppercase()
-        } else {
-            s
-        }
-    }
+    /// Also check that the position is legal, see `Board::validate_position`.
+    Strict,
 }
 
 impl Piece {
-    fn to_fen(&self) -> String {
+    fn to_fen(self) -> String {
         let c: String = self.piece_type.into();
 
         if self.color == Color::White {
@@ -54,7 +103,13 @@ impl Piece {
 }
 
 impl Board {
+    /// Loads a position from FEN, only checking the syntax.
     pub fn from_fen<T: AsRef<str>>(fen: T) -> Result<Board> {
+        Board::from_fen_with_mode(fen, FenMode::Lenient)
+    }
+
+    /// Loads a position from FEN, also checking that the position is legal in strict mode.
+    pub fn from_fen_with_mode<T: AsRef<str>>(fen: T, mode: FenMode) -> Result<Board> {
         let mut board = Board::default();
 
         // Make sure FEN string is split in 6 chunks.
@@ -66,48 +121,18 @@ impl Board {
         let board_state = chunks[0];
         let mut row_count = 0;
         for row in board_state.split('/') {
+            ensure!(row_count < BOARD_DIMENSION, TooManyRows);
             let row_index = BOARD_DIMENSION - 1 - row_count;
             let mut col_index: usize = 0;
             for ch in row.chars() {
-                if ch.is_digit(10) {
+                if ch.is_ascii_digit() {
                     // Empty spaces
                     col_index += ch.to_string().parse::<usize>().unwrap();
                 } else {
                     // Piece.
-                    let mut piece = Piece::from_notation(ch).context(InvalidPiece)?;
+                    let piece = Piece::from_notation(ch).context(InvalidPiece)?;
                     ensure!(col_index < BOARD_DIMENSION, TooManyCols);
-                    ensure!(row_index < BOARD_DIMENSION, TooManyRows);
                     let square = Square::new(Column::try_from(col_index).unwrap(), row_index);
-
-                    match piece.piece_type {
-                        PieceType::King => match piece.color {
-                            Color::White => {
-                                board.white_king_position = square.clone();
-                            }
-                            Color::Black => {
-                                board.black_king_position = square.clone();
-                            }
-                        },
-
-                        PieceType::Rook => match piece.color {
-                            Color::White => {
-                                let rook_squares =
-                                    &[Square::new(Column::A, 0), Square::new(Column::H, 0)];
-                                if !rook_squares.contains(&square) {
-                                    piece.moved_once = true;
-                                }
-                            }
-                            Color::Black => {
-                                let rook_squares =
-                                    &[Square::new(Column::A, 7), Square::new(Column::H, 7)];
-                                if !rook_squares.contains(&square) {
-                                    piece.moved_once = true;
-                                }
-                            }
-                        },
-                        _ => {}
-                    }
-
                     board.place_piece(&square, piece);
                     col_index += 1;
                 }
@@ -120,56 +145,65 @@ impl Board {
         // Load to_play.
         board.to_play = Color::try_from(chunks[1].to_string()).context(InvalidColorToPlay)?;
 
-        // Load castle state.
-        {
-            let white_queenside = chunks[2].contains('Q');
-            let white_kingside = chunks[2].contains('K');
-            let black_kingside = chunks[2].contains('k');
-            let black_queenside = chunks[2].contains('q');
-
-            if !(white_queenside || white_kingside) {
-                // Can't castle at all, mark the king as moved.
-                let white_king_pos = board.white_king_position.clone();
-                let white_king = board.at_mut(&white_king_pos).as_mut().unwrap();
-                white_king.moved_once = true;
-            }
-
-            if !(black_kingside || black_queenside) {
-                // Can't castle at all, mark the king as moved.
-                let black_king_pos = board.black_king_position.clone();
-                let black_king = board.at_mut(&black_king_pos).as_mut().unwrap();
-                black_king.moved_once = true;
-            }
-
-            if !white_queenside {
-                if let Some(p) = board.at_mut(&Square::new(Column::A, 0)) {
-                    p.moved_once = true
-                }
-            }
-            if !white_kingside {
-                if let Some(p) = board.at_mut(&Square::new(Column::H, 0)) {
-                    p.moved_once = true
-                }
-            }
-
-            if !black_kingside {
-                if let Some(p) = board.at_mut(&Square::new(Column::H, 7)) {
-                    p.moved_once = true
+        // Load castle state. Besides `KQkq`, the rook can be given by its file as in Shredder-FEN
+        // (`HAha`) or X-FEN, for Chess960 positions. Lenient parsing skips anything else.
+        if mode == FenMode::Strict {
+            let castling = chunks[2];
+            ensure!(
+                castling == "-"
+                    || (!castling.is_empty()
+                        && castling
+                            .chars()
+                            .all(|ch| matches!(ch, 'K' | 'Q' | 'k' | 'q' | 'A'..='H' | 'a'..='h'))),
+                InvalidCastlingField
+            );
+        }
+        for ch in chunks[2].chars() {
+            let color = if ch.is_ascii_uppercase() {
+                Color::White
+            } else {
+                Color::Black
+            };
+            let king_col = board
+                .king_index(color)
+                .map(|king| Square::from_index(king).col())
+                .unwrap_or(Column::E);
+            let (side, rook_col) = match ch.to_ascii_lowercase() {
+                'k' => (
+                    CastleState::Kingside,
+                    board.outermost_rook(color, CastleState::Kingside),
+                ),
+                'q' => (
+                    CastleState::Queenside,
+                    board.outermost_rook(color, CastleState::Queenside),
+                ),
+                file @ 'a'..='h' => {
+                    let col = Column::try_from((file as u8 - b'a') as usize).unwrap();
+                    let side = if col > king_col {
+                        CastleState::Kingside
+                    } else {
+                        CastleState::Queenside
+                    };
+                    (side, col)
                 }
-            }
+                _ => continue,
+            };
 
-            if !black_queenside {
-                if let Some(p) = board.at_mut(&Square::new(Column::A, 7)) {
-                    p.moved_once = true
-                }
-            }
+            let state = &mut board.castle_states[color.index()];
+            *state = state.with(side);
+            let side_index = if side == CastleState::Kingside { 0 } else { 1 };
+            board.castling_rook_cols[color.index()][side_index] = rook_col;
         }
 
         // Load en-passant square.
         if chunks[3] != "-" {
-            // We have an en-passant square.
             let sq = Square::from_notation(chunks[3]).context(InvalidEnPassant)?;
-            board.en_passant_square = Some(sq)
+            let target_row = match board.to_play {
+                Color::White => BLACK_PAWN_ROW - 1,
+                Color::Black => WHITE_PAWN_ROW + 1,
+            };
+            ensure!(sq.row() == target_row, InvalidEnPassantRank);
+            board.en_passant_square = Some(sq);
         }
 
         // Load half-move clock.
@@ -178,9 +212,146 @@ impl Board {
         // Load full-move clock.
         board.full_move_clock = chunks[5].parse::<usize>().context(InvalidFullMoveClock)?;
 
+        board.reset_hash();
+
+        if mode == FenMode::Strict {
+            board.validate_position()?;
+        }
+
         Ok(board)
     }
 
+    /// Checks that the position could arise in a game: one king per side, no pawn on the first
+    /// or last rank, no more pieces than a side starts with (counting promotions), the side that
+    /// just moved not in check, at most two checkers, and castling rights and en-passant square
+    /// consistent with the pieces on the board.
+    pub fn validate_position(&self) -> Result<()> {
+        for color in &[Color::White, Color::Black] {
+            let color = *color;
+            let kings = self.pieces_of(PieceType::King, color).count();
+            ensure!(kings > 0, MissingKing { color });
+            ensure!(kings == 1, TooManyKings { color });
+            ensure!(
+                self.pieces_of(PieceType::Pawn, color).count() <= 8,
+                TooManyPawns { color }
+            );
+            ensure!(
+                self.colors[color.index()].count() <= 16,
+                TooManyPieces { color }
+            );
+        }
+
+        let back_ranks = Square::new(Column::A, WHITE_PIECES_ROW)
+            .rank()
+            .chain(Square::new(Column::A, BLACK_PIECES_ROW).rank());
+        for square in back_ranks {
+            let is_pawn = self
+                .at(&square)
+                .map(|p| p.piece_type == PieceType::Pawn)
+                .unwrap_or(false);
+            ensure!(!is_pawn, PawnOnBackRank { square });
+        }
+
+        ensure!(
+            !self.validate_check(self.to_play.opposite()),
+            OpponentInCheck
+        );
+        ensure!(self.checkers_bitboard().count() <= 2, TooManyCheckers);
+
+        for color in &[Color::White, Color::Black] {
+            let color = *color;
+            let castle_state = self.get_castle_state(color);
+            if castle_state == CastleState::None {
+                continue;
+            }
+
+            // The king can start on any file in Chess960, but always between its rooks.
+            let king = self.king_index(color).unwrap();
+            ensure!(
+                king / BOARD_DIMENSION == home_row(color),
+                InvalidCastlingRights { color }
+            );
+            for side in &[CastleState::Kingside, CastleState::Queenside] {
+                if castle_state.allows(*side) {
+                    let rook = self.castling_rook(color, *side);
+                    let rook_on_side = if *side == CastleState::Kingside {
+                        rook > king
+                    } else {
+                        rook < king
+                    };
+                    ensure!(
+                        rook_on_side
+                            && self.piece_at(rook) == Some(Piece::new(PieceType::Rook, color)),
+                        InvalidCastlingRights { color }
+                    );
+                }
+            }
+        }
+
+        if let Some(target) = &self.en_passant_square {
+            // The pawn moved from the square behind the target to the one in front of it.
+            let (pawn_row_delta, pusher) = match self.to_play {
+                Color::White => (-1, Color::Black),
+                Color::Black => (1, Color::White),
+            };
+            let pawn = target.relative(0, pawn_row_delta);
+            let origin = target.relative(0, -pawn_row_delta);
+            let pawn_is_there = pawn
+                .and_then(|s| self.at(&s).copied())
+                .map(|p| p == Piece::new(PieceType::Pawn, pusher))
+                .unwrap_or(false);
+            let path_is_empty =
+                self.at(target).is_none() && origin.map(|s| self.at(&s).is_none()).unwrap_or(false);
+            ensure!(pawn_is_there && path_is_empty, EnPassantWithoutPawn);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the file of the rook furthest from the king on a given side of its row, the
+    /// one `K` and `Q` refer to in X-FEN. Defaults to the corner when there is none.
+    fn outermost_rook(&self, color: Color, side: CastleState) -> Column {
+        let row = home_row(color);
+        let rook = Piece::new(PieceType::Rook, color);
+        let cols: Vec<usize> = match side {
+            CastleState::Kingside => (0..BOARD_DIMENSION).rev().collect(),
+            _ => (0..BOARD_DIMENSION).collect(),
+        };
+        cols.into_iter()
+            .map(|col| Column::try_from(col).unwrap())
+            .find(|col| self.at(&Square::new(*col, row)) == Some(&rook))
+            .unwrap_or(match side {
+                CastleState::Kingside => Column::H,
+                _ => Column::A,
+            })
+    }
+
+    /// Returns the castling rights in X-FEN: `KQkq` when castling with the outermost rooks, as
+    /// in classical chess, and the file of the rook otherwise.
+    fn castling_fen(&self) -> String {
+        let mut fen = String::new();
+        for color in &[Color::White, Color::Black] {
+            for (side, letter) in &[(CastleState::Kingside, 'k'), (CastleState::Queenside, 'q')] {
+                if !self.get_castle_state(*color).allows(*side) {
+                    continue;
+                }
+
+                let rook_col = Square::from_index(self.castling_rook(*color, *side)).col();
+                let ch = if rook_col == self.outermost_rook(*color, *side) {
+                    *letter
+                } else {
+                    (b'a' + usize::from(rook_col) as u8) as char
+                };
+                fen.push(if *color == Color::White {
+                    ch.to_ascii_uppercase()
+                } else {
+                    ch
+                });
+            }
+        }
+        fen
+    }
+
     pub fn to_fen(&self) -> String {
         let mut fen_board_rows: [String; 8] = Default::default();
 
@@ -209,16 +380,24 @@ impl Board {
         }
 
         let en_passant_square_notation = match self.en_passant_square.as_ref() {
-            Some(s) => String::from(s.clone()),
+            Some(s) => s.to_string(),
             None => String::from("-"),
         };
 
+        let castle_notation = {
+            let castle_states = self.castling_fen();
+            if castle_states.is_empty() {
+                String::from("-")
+            } else {
+                castle_states
+            }
+        };
+
         format!(
-            "{} {} {}{} {} {} {}",
+            "{} {} {} {} {} {}",
             fen_board_rows.join("/"),
             String::from(self.to_play),
-            self.get_castle_state(Color::White).to_fen(Color::White),
-            self.get_castle_state(Color::Black).to_fen(Color::Black),
+            castle_notation,
             en_passant_square_notation,
             self.half_move_clock,
             self.full_move_clock
@@ -0,0 +1,189 @@
+use crate::board::CastleState;
+use crate::constants::*;
+use crate::zobrist;
+use crate::{Board, Color, Move, MoveKind, Piece, PieceType, Square};
+
+/// The state lost when playing a move in place, needed to take it back.
+#[derive(Clone, Debug)]
+pub struct UndoInfo {
+    captured: Option<Piece>,
+    castle_states: [CastleState; 2],
+    en_passant_square: Option<Square>,
+    half_move_clock: usize,
+    full_move_clock: usize,
+    hash: u64,
+}
+
+/// Returns the side a castling move castles towards.
+fn castling_side(mv: &Move) -> CastleState {
+    if mv.kind == MoveKind::KingsideCastle {
+        CastleState::Kingside
+    } else {
+        CastleState::Queenside
+    }
+}
+
+/// Returns the square of the pawn taken by an en-passant capture.
+fn en_passant_capture_square(mv: &Move, color: Color) -> usize {
+    let dst = mv.dst.index();
+    match color {
+        Color::White => dst - BOARD_DIMENSION,
+        Color::Black => dst + BOARD_DIMENSION,
+    }
+}
+
+impl Board {
+    /// Plays a move in place, without checking that it is legal.
+    ///
+    /// The move must have its details filled in and be legal in this position, like the moves
+    /// returned by `legal_moves` or `validate_move`. Use `move_piece` to play a move that wasn't
+    /// validated.
+    ///
+    /// Returns what is needed to take the move back with `unmake_move`.
+    pub fn make_move(&mut self, mv: Move) -> UndoInfo {
+        let src = mv.src.index();
+        let dst = mv.dst.index();
+        let color = self.to_play;
+
+        let mut undo = UndoInfo {
+            captured: None,
+            castle_states: self.castle_states,
+            en_passant_square: self.en_passant_square,
+            half_move_clock: self.half_move_clock,
+            full_move_clock: self.full_move_clock,
+            hash: self.hash,
+        };
+
+        // Whether the en-passant square counts in the hash depends on the pawns around it, so
+        // clear it before they move.
+        self.set_en_passant_square(None);
+
+        let piece = self.remove_piece(src).unwrap();
+
+        if mv.is_castle() {
+            // We need to move the corresponding rook as well. Both are lifted before being put
+            // down, as in Chess960 they can land on each other's square.
+            let castling = self.castling(color, castling_side(&mv), src);
+            let rook = self.remove_piece(castling.rook_src).unwrap();
+            self.put_piece(castling.king_dst, piece);
+            self.put_piece(castling.rook_dst, rook);
+        } else {
+            undo.captured = if mv.kind == MoveKind::EnPassant {
+                self.remove_piece(en_passant_capture_square(&mv, color))
+            } else {
+                self.remove_piece(dst)
+            };
+
+            // Swap the pawn for its promoted piece.
+            let placed = match mv.promotion {
+                Some(promoted_type) => Piece::new(promoted_type, color),
+                None => piece,
+            };
+            self.put_piece(dst, placed);
+        }
+
+        // Moving the king loses both castling rights, and anything moving from or to the square
+        // of a castling rook loses the right to castle with that rook.
+        if piece.piece_type == PieceType::King {
+            self.set_castle_state(color, CastleState::None);
+        }
+        for index in &[src, dst] {
+            if let Some((rook_color, side)) = self.castling_rook_side(*index) {
+                let state = self.get_castle_state(rook_color);
+                self.set_castle_state(rook_color, state.without(side));
+            }
+        }
+
+        if mv.kind == MoveKind::DoublePawnPush {
+            self.set_en_passant_square(Some(Square::from_index((src + dst) / 2)));
+        }
+
+        if piece.piece_type == PieceType::Pawn || undo.captured.is_some() {
+            self.half_move_clock = 0;
+        } else {
+            self.half_move_clock += 1;
+        }
+
+        if color == Color::Black {
+            self.full_move_clock += 1;
+        }
+
+        self.to_play = color.opposite();
+        self.hash ^= zobrist::black_to_play_key();
+
+        undo
+    }
+
+    /// Takes back a move played with `make_move`, restoring the position exactly.
+    pub fn unmake_move(&mut self, mv: Move, undo: UndoInfo) {
+        let src = mv.src.index();
+        let dst = mv.dst.index();
+        let color = self.to_play.opposite();
+
+        if mv.is_castle() {
+            let castling = self.castling(color, castling_side(&mv), src);
+            let king = self.remove_piece(castling.king_dst).unwrap();
+            let rook = self.remove_piece(castling.rook_dst).unwrap();
+            self.put_piece(src, king);
+            self.put_piece(castling.rook_src, rook);
+        } else {
+            let placed = self.remove_piece(dst).unwrap();
+            let piece = match mv.promotion {
+                Some(_) => Piece::new(PieceType::Pawn, color),
+                None => placed,
+            };
+            self.put_piece(src, piece);
+        }
+
+        if let Some(captured) = undo.captured {
+            let captured_square = if mv.kind == MoveKind::EnPassant {
+                en_passant_capture_square(&mv, color)
+            } else {
+                dst
+            };
+            self.put_piece(captured_square, captured);
+        }
+
+        self.to_play = color;
+        self.castle_states = undo.castle_states;
+        self.en_passant_square = undo.en_passant_square;
+        self.half_move_clock = undo.half_move_clock;
+        self.full_move_clock = undo.full_move_clock;
+        self.hash = undo.hash;
+    }
+
+    /// Passes the turn to the opponent without moving, which the search uses to find out
+    /// whether a position is still good after giving the opponent a free move.
+    ///
+    /// Returns what is needed to take it back with `unmake_null_move`.
+    pub(crate) fn make_null_move(&mut self) -> UndoInfo {
+        let undo = UndoInfo {
+            captured: None,
+            castle_states: self.castle_states,
+            en_passant_square: self.en_passant_square,
+            half_move_clock: self.half_move_clock,
+            full_move_clock: self.full_move_clock,
+            hash: self.hash,
+        };
+
+        self.set_en_passant_square(None);
+        self.half_move_clock += 1;
+        if self.to_play == Color::Black {
+            self.full_move_clock += 1;
+        }
+
+        self.to_play = self.to_play.opposite();
+        self.hash ^= zobrist::black_to_play_key();
+
+        undo
+    }
+
+    /// Takes back a turn passed with `make_null_move`.
+    pub(crate) fn unmake_null_move(&mut self, undo: UndoInfo) {
+        self.to_play = self.to_play.opposite();
+        self.en_passant_square = undo.en_passant_square;
+        self.half_move_clock = undo.half_move_clock;
+        self.full_move_clock = undo.full_move_clock;
+        self.hash = undo.hash;
+    }
+}
@@ -0,0 +1,177 @@
+use crate::attacks;
+use crate::bitboard::Bitboard;
+use crate::board::CastleState;
+use crate::constants::*;
+use crate::{Board, Color, Move, MoveKind, Piece, PieceType, Square};
+
+const PROMOTION_TYPES: &[PieceType] = &[
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+impl Board {
+    /// Returns every legal move for the side to play.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.generate_legal_moves(self.colors[self.to_play.index()])
+    }
+
+    /// Returns every legal move of the piece on a given square.
+    ///
+    /// The list is empty if the square is empty or if the piece doesn't belong to the side to
+    /// play.
+    pub fn legal_moves_from(&self, src: &Square) -> Vec<Move> {
+        let from = Bitboard::from_index(src.index()) & self.colors[self.to_play.index()];
+        self.generate_legal_moves(from)
+    }
+
+    /// Generates the legal moves of the pieces of the side to play standing on `from`.
+    ///
+    /// Moves are first generated from the attack tables without worrying about the king. Moves
+    /// of pieces that aren't pinned are then legal as long as the king isn't in check. King
+    /// moves, en-passant captures and check evasions are played to make sure they leave the king
+    /// safe.
+    fn generate_legal_moves(&self, from: Bitboard) -> Vec<Move> {
+        let mut moves = Vec::with_capacity(64);
+        for src in from {
+            self.generate_piece_moves(src, &mut moves);
+        }
+
+        let color = self.to_play;
+        let king = self.king_index(color);
+        let in_check = !self.checkers_bitboard().is_empty();
+        let pinned = self
+            .pins(color)
+            .into_iter()
+            .fold(Bitboard::EMPTY, |bb, (pinned, _)| {
+                bb | Bitboard::from_index(pinned)
+            });
+
+        let mut position = self.clone();
+        moves.retain(|mv| {
+            let src = mv.src.index();
+            if !in_check && Some(src) != king && mv.kind != MoveKind::EnPassant {
+                // A pinned piece can still move along the line through its king.
+                return !pinned.contains(src)
+                    || attacks::line(king.unwrap(), src).contains(mv.dst.index());
+            }
+
+            let undo = position.make_move(mv.clone());
+            let legal = !position.validate_check(color);
+            position.unmake_move(mv.clone(), undo);
+            legal
+        });
+        moves
+    }
+
+    fn generate_piece_moves(&self, src: usize, moves: &mut Vec<Move>) {
+        // Callers only ask for squares holding a piece of the side to play.
+        let piece = self.piece_at(src).unwrap();
+        let own = self.colors[piece.color.index()];
+        let occupied = self.occupied();
+
+        let targets = match piece.piece_type {
+            PieceType::Pawn => return self.generate_pawn_moves(src, piece.color, moves),
+            PieceType::Knight => attacks::knight_attacks(src),
+            PieceType::Bishop => attacks::bishop_attacks(src, occupied),
+            PieceType::Rook => attacks::rook_attacks(src, occupied),
+            PieceType::Queen => attacks::queen_attacks(src, occupied),
+            PieceType::King => {
+                self.generate_castling_moves(src, piece.color, moves);
+                attacks::king_attacks(src)
+            }
+        };
+
+        for dst in targets & !own {
+            moves.push(self.resolved_move(src, dst, piece, None, MoveKind::Normal));
+        }
+    }
+
+    fn generate_pawn_moves(&self, src: usize, color: Color, moves: &mut Vec<Move>) {
+        let (forward, start_row, last_row) = match color {
+            Color::White => (BOARD_DIMENSION as i32, WHITE_PAWN_ROW, BLACK_PIECES_ROW),
+            Color::Black => (-(BOARD_DIMENSION as i32), BLACK_PAWN_ROW, WHITE_PIECES_ROW),
+        };
+        let piece = Piece::new(PieceType::Pawn, color);
+        let occupied = self.occupied();
+
+        let mut push = |dst: usize, kind: MoveKind| {
+            if dst / BOARD_DIMENSION == last_row {
+                for promotion in PROMOTION_TYPES {
+                    moves.push(self.resolved_move(src, dst, piece, Some(*promotion), kind));
+                }
+            } else {
+                moves.push(self.resolved_move(src, dst, piece, None, kind));
+            }
+        };
+
+        // Pushes. Positions loaded leniently can have a pawn on its last row, which can't move.
+        let single = (src as i32 + forward) as usize;
+        if src / BOARD_DIMENSION != last_row && !occupied.contains(single) {
+            push(single, MoveKind::Normal);
+
+            let double = (single as i32 + forward) as usize;
+            if src / BOARD_DIMENSION == start_row && !occupied.contains(double) {
+                push(double, MoveKind::DoublePawnPush);
+            }
+        }
+
+        // Captures.
+        let pawn_attacks = attacks::pawn_attacks(color, src);
+        for dst in pawn_attacks & self.colors[color.opposite().index()] {
+            push(dst, MoveKind::Normal);
+        }
+
+        if let Some(en_passant_square) = &self.en_passant_square {
+            let dst = en_passant_square.index();
+            if pawn_attacks.contains(dst) {
+                push(dst, MoveKind::EnPassant);
+            }
+        }
+    }
+
+    fn generate_castling_moves(&self, src: usize, color: Color, moves: &mut Vec<Move>) {
+        if self.get_castle_state(color) == CastleState::None {
+            return;
+        }
+
+        for (side, kind) in &[
+            (CastleState::Kingside, MoveKind::KingsideCastle),
+            (CastleState::Queenside, MoveKind::QueensideCastle),
+        ] {
+            if let Ok(castling) = self.validate_castling(color, *side, src) {
+                moves.push(self.resolved_move(
+                    src,
+                    castling.move_dst(),
+                    Piece::new(PieceType::King, color),
+                    None,
+                    *kind,
+                ));
+            }
+        }
+    }
+
+    /// Builds a move with its details filled in.
+    fn resolved_move(
+        &self,
+        src: usize,
+        dst: usize,
+        piece: Piece,
+        promotion: Option<PieceType>,
+        kind: MoveKind,
+    ) -> Move {
+        let captured = match kind {
+            MoveKind::EnPassant => Some(PieceType::Pawn),
+            MoveKind::KingsideCastle | MoveKind::QueensideCastle => None,
+            _ => self.piece_at(dst).map(|p| p.piece_type),
+        };
+
+        Move {
+            piece: Some(piece.piece_type),
+            captured,
+            kind,
+            ..Move::new(Square::from_index(src), Square::from_index(dst), promotion)
+        }
+    }
+}
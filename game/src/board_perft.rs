@@ -0,0 +1,50 @@
+use crate::{Board, Move};
+
+impl Board {
+    /// Counts the leaf nodes of the legal move tree down to a given depth.
+    ///
+    /// Comparing this number with known values is the standard way to validate move generation.
+    pub fn perft(&self, depth: usize) -> u64 {
+        self.clone().perft_in_place(depth)
+    }
+
+    /// Walks the tree by playing and taking back moves on a single board.
+    fn perft_in_place(&mut self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        moves
+            .into_iter()
+            .map(|mv| {
+                let undo = self.make_move(mv.clone());
+                let nodes = self.perft_in_place(depth - 1);
+                self.unmake_move(mv, undo);
+                nodes
+            })
+            .sum()
+    }
+
+    /// Same as `perft`, but with the node count split by first move.
+    pub fn perft_divide(&self, depth: usize) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        let mut position = self.clone();
+        self.legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let undo = position.make_move(mv.clone());
+                let nodes = position.perft_in_place(depth - 1);
+                position.unmake_move(mv.clone(), undo);
+                (mv, nodes)
+            })
+            .collect()
+    }
+}
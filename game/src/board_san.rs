@@ -0,0 +1,189 @@
+use std::convert::TryFrom;
+
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+
+use crate::board::MoveError;
+use crate::{Board, Column, GameStatus, Move, MoveKind, NotationError, PieceType, Square};
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum SanError {
+    #[snafu(display("Invalid move notation."))]
+    InvalidNotation,
+
+    #[snafu(display("Invalid square."))]
+    InvalidDestination { source: NotationError },
+
+    #[snafu(display("No legal move matches this notation."))]
+    NoMatchingMove,
+
+    #[snafu(display("This notation matches more than one move."))]
+    AmbiguousMove,
+}
+
+impl SanError {
+    /// A stable, machine-readable code for the error, unlike its message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SanError::InvalidNotation => "invalid_notation",
+            SanError::InvalidDestination { .. } => "invalid_square",
+            SanError::NoMatchingMove => "no_matching_move",
+            SanError::AmbiguousMove => "ambiguous_move",
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, SanError>;
+
+fn piece_letter(piece_type: PieceType) -> String {
+    String::from(piece_type).to_uppercase()
+}
+
+impl Board {
+    /// Parses a move in Standard Algebraic Notation (e.g. "Nf3", "exd5", "O-O" or "e8=Q+").
+    ///
+    /// Returns the matching legal move, with its details filled in.
+    pub fn parse_san(&self, san: &str) -> Result<Move> {
+        // Check & mate markers and annotations don't change the move.
+        let san = san.trim().trim_end_matches(|c| "+#!?".contains(c));
+        ensure!(!san.is_empty(), InvalidNotation);
+
+        let candidates = self.legal_moves();
+
+        let matching: Vec<Move> = match san {
+            "O-O" | "0-0" => candidates
+                .into_iter()
+                .filter(|mv| mv.kind == MoveKind::KingsideCastle)
+                .collect(),
+            "O-O-O" | "0-0-0" => candidates
+                .into_iter()
+                .filter(|mv| mv.kind == MoveKind::QueensideCastle)
+                .collect(),
+            _ => {
+                let mut chars: Vec<char> = san.chars().collect();
+
+                // Promotion, either as "e8=Q" or "e8Q".
+                let promotion = match chars.last() {
+                    Some(c) if "QRBN".contains(*c) && chars.len() > 2 => {
+                        let p = PieceType::try_from(c.to_string()).ok();
+                        chars.pop();
+                        if chars.last() == Some(&'=') {
+                            chars.pop();
+                        }
+                        p
+                    }
+                    _ => None,
+                };
+
+                // Moving piece, pawns have no letter.
+                let piece_type = match chars.first() {
+                    Some(c) if "KQRBN".contains(*c) => {
+                        let p = PieceType::try_from(c.to_string()).ok();
+                        chars.remove(0);
+                        p.context(InvalidNotation)?
+                    }
+                    _ => PieceType::Pawn,
+                };
+
+                ensure!(chars.len() >= 2, InvalidNotation);
+                let dst_notation: String = chars.split_off(chars.len() - 2).into_iter().collect();
+                let dst = Square::from_notation(dst_notation).context(InvalidDestination)?;
+
+                if chars.last() == Some(&'x') {
+                    chars.pop();
+                }
+
+                // What's left is the disambiguation: the source file, rank, or both.
+                let mut src_col = None;
+                let mut src_row = None;
+                for c in chars {
+                    if let Some(digit) = c.to_digit(10) {
+                        ensure!(
+                            src_row.is_none() && (1..=8).contains(&digit),
+                            InvalidNotation
+                        );
+                        src_row = Some(digit as usize - 1);
+                    } else {
+                        ensure!(src_col.is_none(), InvalidNotation);
+                        let col = Column::try_from(c.to_string().to_uppercase()).ok();
+                        src_col = Some(col.context(InvalidNotation)?);
+                    }
+                }
+
+                candidates
+                    .into_iter()
+                    .filter(|mv| {
+                        mv.piece == Some(piece_type)
+                            && mv.dst == dst
+                            && mv.promotion == promotion
+                            && !mv.is_castle()
+                            && src_col.map(|c| c == mv.src.col()).unwrap_or(true)
+                            && src_row.map(|r| r == mv.src.row()).unwrap_or(true)
+                    })
+                    .collect()
+            }
+        };
+
+        ensure!(matching.len() < 2, AmbiguousMove);
+        matching.into_iter().next().context(NoMatchingMove)
+    }
+
+    /// Formats a legal move in Standard Algebraic Notation.
+    pub fn to_san(&self, mv: &Move) -> std::result::Result<String, MoveError> {
+        let (new_position, mv) = self.move_piece(mv)?;
+
+        let mut san = match mv.kind {
+            MoveKind::KingsideCastle => String::from("O-O"),
+            MoveKind::QueensideCastle => String::from("O-O-O"),
+            _ => {
+                let piece_type = mv.piece.unwrap(); // Unwrap safe because the move was played.
+                let mut san = String::default();
+
+                if piece_type == PieceType::Pawn {
+                    if mv.is_capture() {
+                        san += &String::from(mv.src.col());
+                    }
+                } else {
+                    san += &piece_letter(piece_type);
+
+                    // Disambiguate between pieces of the same type that can reach the square.
+                    let others: Vec<Move> = self
+                        .legal_moves()
+                        .into_iter()
+                        .filter(|other| {
+                            other.piece == mv.piece && other.dst == mv.dst && other.src != mv.src
+                        })
+                        .collect();
+
+                    if !others.is_empty() {
+                        if others.iter().all(|other| other.src.col() != mv.src.col()) {
+                            san += &String::from(mv.src.col());
+                        } else if others.iter().all(|other| other.src.row() != mv.src.row()) {
+                            san += &(mv.src.row() + 1).to_string();
+                        } else {
+                            san += &mv.src.to_string();
+                        }
+                    }
+                }
+
+                if mv.is_capture() {
+                    san += "x";
+                }
+                san += &mv.dst.to_string();
+
+                if let Some(promotion) = mv.promotion {
+                    san += &format!("={}", piece_letter(promotion));
+                }
+
+                san
+            }
+        };
+
+        match new_position.status() {
+            GameStatus::Checkmate { .. } => san += "#",
+            _ if new_position.is_check() => san += "+",
+            _ => {}
+        }
+
+        Ok(san)
+    }
+}
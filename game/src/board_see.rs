@@ -0,0 +1,111 @@
+use crate::bitboard::Bitboard;
+use crate::constants::*;
+use crate::{Board, Color, Move, PieceType};
+
+/// Piece values used to weigh exchanges, indexed by `PieceType::index`. The king is worth more
+/// than everything else together, as it can never be given up.
+pub(crate) const SEE_VALUES: [i32; 6] = [100, 300, 300, 500, 900, 20_000];
+
+/// Piece types from the least to the most valuable, the order in which they join an exchange.
+const EXCHANGE_ORDER: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+impl Board {
+    /// Static exchange evaluation: the material won by playing a move, assuming both sides
+    /// then keep capturing on its destination square with their least valuable piece, and stop
+    /// as soon as it stops paying off.
+    ///
+    /// Pieces lined up behind an attacker on the same line (x-rays) join the exchange once the
+    /// attacker in front of them has captured. Pins are not taken into account.
+    ///
+    /// A negative value means the moving piece can be won for less than it is worth, and zero
+    /// is returned for castling and moves from an empty square.
+    pub fn see(&self, mv: &Move) -> i32 {
+        let src = mv.src.index();
+        let dst = mv.dst.index();
+        let piece = match self.piece_at(src) {
+            Some(piece) => piece,
+            None => return 0,
+        };
+        if self.castling_side(&mv.src, &mv.dst).is_some() {
+            return 0;
+        }
+
+        let mut occupied = self.occupied() ^ Bitboard::from_index(src);
+        let is_en_passant = piece.piece_type == PieceType::Pawn
+            && mv.src.col() != mv.dst.col()
+            && self.en_passant_square == Some(mv.dst);
+
+        // What each capture of the sequence wins, before the other side answers.
+        let mut gains = Vec::with_capacity(32);
+        gains.push(if is_en_passant {
+            let taken = match piece.color {
+                Color::White => dst - BOARD_DIMENSION,
+                Color::Black => dst + BOARD_DIMENSION,
+            };
+            occupied ^= Bitboard::from_index(taken);
+            SEE_VALUES[PieceType::Pawn.index()]
+        } else {
+            self.piece_at(dst)
+                .map(|p| SEE_VALUES[p.piece_type.index()])
+                .unwrap_or(0)
+        });
+
+        // The value of the piece standing on the square, which the next capture takes.
+        let mut on_square = SEE_VALUES[piece.piece_type.index()];
+        if let Some(promotion) = mv.promotion {
+            gains[0] += SEE_VALUES[promotion.index()] - SEE_VALUES[PieceType::Pawn.index()];
+            on_square = SEE_VALUES[promotion.index()];
+        }
+
+        let last_row =
+            dst / BOARD_DIMENSION == WHITE_PIECES_ROW || dst / BOARD_DIMENSION == BLACK_PIECES_ROW;
+        let mut color = piece.color.opposite();
+        loop {
+            // Sliders are looked up again through the squares emptied so far, revealing x-rays.
+            let attackers = self.attackers_to(dst, occupied) & occupied;
+            let own = attackers & self.colors[color.index()];
+            let (attacker_type, attacker) = match EXCHANGE_ORDER
+                .iter()
+                .find_map(|t| (own & self.pieces[t.index()]).first().map(|sq| (*t, sq)))
+            {
+                Some(attacker) => attacker,
+                None => break,
+            };
+
+            // The king can only capture a piece nobody defends.
+            if attacker_type == PieceType::King
+                && !(attackers & self.colors[color.opposite().index()]).is_empty()
+            {
+                break;
+            }
+
+            let mut gain = on_square - gains[gains.len() - 1];
+            on_square = SEE_VALUES[attacker_type.index()];
+            if attacker_type == PieceType::Pawn && last_row {
+                let promotion_gain =
+                    SEE_VALUES[PieceType::Queen.index()] - SEE_VALUES[PieceType::Pawn.index()];
+                gain += promotion_gain;
+                on_square += promotion_gain;
+            }
+            gains.push(gain);
+
+            occupied ^= Bitboard::from_index(attacker);
+            color = color.opposite();
+        }
+
+        // Walk the sequence back, each side only capturing when it doesn't lose by it.
+        while gains.len() > 1 {
+            let last = gains.pop().unwrap();
+            let previous = gains.last_mut().unwrap();
+            *previous = -(-*previous).max(last);
+        }
+        gains[0]
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+
+use crate::bitboard::Bitboard;
+use crate::{Board, Color, PieceType};
+
+/// Number of half-moves without a capture or pawn move after which either player can claim a
+/// draw.
+const FIFTY_MOVE_RULE_HALF_MOVES: usize = 100;
+
+/// Number of half-moves without a capture or pawn move after which the game is automatically
+/// drawn.
+const SEVENTY_FIVE_MOVE_RULE_HALF_MOVES: usize = 150;
+
+/// The light squares of the board.
+const LIGHT_SQUARES: Bitboard = Bitboard(0x55AA_55AA_55AA_55AA);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DrawReason {
+    /// Fifty moves without a capture or pawn move, claimed by a player.
+    FiftyMoveRule,
+
+    /// Seventy-five moves without a capture or pawn move.
+    SeventyFiveMoveRule,
+
+    /// The same position occurred three times, claimed by a player.
+    ThreefoldRepetition,
+
+    /// The same position occurred five times.
+    FivefoldRepetition,
+
+    /// Neither side has enough material left to checkmate.
+    InsufficientMaterial,
+}
+
+impl DrawReason {
+    /// Whether the draw has to be claimed by a player, rather than ending the game on its own.
+    pub fn is_claimable(&self) -> bool {
+        matches!(
+            self,
+            DrawReason::FiftyMoveRule | DrawReason::ThreefoldRepetition
+        )
+    }
+}
+
+impl fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            DrawReason::FiftyMoveRule => "fifty-move rule",
+            DrawReason::SeventyFiveMoveRule => "seventy-five-move rule",
+            DrawReason::ThreefoldRepetition => "threefold repetition",
+            DrawReason::FivefoldRepetition => "fivefold repetition",
+            DrawReason::InsufficientMaterial => "insufficient material",
+        };
+
+        write!(f, "{}", reason)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameStatus {
+    Ongoing,
+    Checkmate { winner: Color },
+    Stalemate,
+    Draw { reason: DrawReason },
+}
+
+impl GameStatus {
+    /// Whether the game has ended.
+    pub fn is_over(&self) -> bool {
+        *self != GameStatus::Ongoing
+    }
+}
+
+impl fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameStatus::Ongoing => write!(f, "Game in progress."),
+            GameStatus::Checkmate { winner } => match winner {
+                Color::White => write!(f, "Checkmate, white wins."),
+                Color::Black => write!(f, "Checkmate, black wins."),
+            },
+            GameStatus::Stalemate => write!(f, "Stalemate."),
+            GameStatus::Draw { reason } => write!(f, "Draw by {}.", reason),
+        }
+    }
+}
+
+impl Board {
+    /// Whether the side to play is in check.
+    pub fn is_check(&self) -> bool {
+        self.validate_check(self.to_play)
+    }
+
+    /// Whether neither side can possibly checkmate: king against king, a single minor piece
+    /// against a bare king, or bishops that all stand on squares of the same color.
+    pub fn has_insufficient_material(&self) -> bool {
+        let heavy = self.pieces[PieceType::Pawn.index()]
+            | self.pieces[PieceType::Rook.index()]
+            | self.pieces[PieceType::Queen.index()];
+        if !heavy.is_empty() {
+            return false;
+        }
+
+        let knights = self.pieces[PieceType::Knight.index()];
+        let bishops = self.pieces[PieceType::Bishop.index()];
+        if (knights | bishops).count() <= 1 {
+            return true;
+        }
+
+        knights.is_empty()
+            && ((bishops & LIGHT_SQUARES).is_empty() || (bishops & !LIGHT_SQUARES).is_empty())
+    }
+
+    /// Returns the draw the side to play could claim in this position, if any.
+    ///
+    /// Only the fifty-move rule can be decided from a single position, see
+    /// `Game::claimable_draw` for repetitions.
+    pub fn claimable_draw(&self) -> Option<DrawReason> {
+        if self.half_move_clock >= FIFTY_MOVE_RULE_HALF_MOVES && !self.status().is_over() {
+            Some(DrawReason::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the status of the game in this position.
+    ///
+    /// Repetitions depend on the moves that led to the position, see `Game::status`.
+    pub fn status(&self) -> GameStatus {
+        if self.legal_moves().is_empty() {
+            if self.is_check() {
+                return GameStatus::Checkmate {
+                    winner: self.to_play.opposite(),
+                };
+            }
+            return GameStatus::Stalemate;
+        }
+
+        if self.half_move_clock >= SEVENTY_FIVE_MOVE_RULE_HALF_MOVES {
+            return GameStatus::Draw {
+                reason: DrawReason::SeventyFiveMoveRule,
+            };
+        }
+
+        if self.has_insufficient_material() {
+            return GameStatus::Draw {
+                reason: DrawReason::InsufficientMaterial,
+            };
+        }
+
+        GameStatus::Ongoing
+    }
+}
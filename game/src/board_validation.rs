@@ -1,63 +1,22 @@
-use snafu::ensure;
+use snafu::{ensure, OptionExt};
 
+use crate::attacks;
+use crate::bitboard::Bitboard;
 use crate::board::*;
 use crate::constants::*;
-use crate::{Color, Column, Piece, Square};
+use crate::{Color, Piece, PieceType, Square};
 
 impl Board {
-    pub(crate) fn validate_line_clear(
-        &self,
-        src: &Square,
-        dst: &Square,
-        col_delta: i32,
-        row_delta: i32,
-    ) -> Result<(), MoveError> {
-        let mut s = src.clone();
-        loop {
-            s = s.relative(col_delta, row_delta).unwrap();
-            if &s == dst {
-                break;
-            }
-            ensure!(self.at(&s).is_none(), PathObstructed)
-        }
-
-        Ok(())
-    }
-
-    pub(crate) fn validate_line_threat(
-        &self,
-        src: &Square,
-        dst: &Square,
-        col_delta: i32,
-        row_delta: i32,
-        by_color: Color,
-    ) -> Result<(), MoveError> {
-        let mut s = src.clone();
-        ensure!(
-            !self.validate_square_threatened(src, by_color),
-            CannotCastle
-        );
-
-        loop {
-            s = s.relative(col_delta, row_delta).unwrap();
-            if &s == dst {
-                break;
-            }
-            ensure!(
-                !self.validate_square_threatened(&s, by_color),
-                CannotCastleThroughCheck
-            );
-        }
-
+    /// Validates that nothing stands between two squares on the same line.
+    pub(crate) fn validate_line_clear(&self, src: &Square, dst: &Square) -> Result<(), MoveError> {
+        let path = attacks::between(src.index(), dst.index());
+        ensure!((path & self.occupied()).is_empty(), PathObstructed);
         Ok(())
     }
 
     pub(crate) fn validate_knight(&self, src: &Square, dst: &Square) -> Result<(), MoveError> {
-        let coll_diff_abs = ((usize::from(src.col) as i16) - (usize::from(dst.col) as i16)).abs();
-        let row_diff_abs = ((src.row as i16) - (dst.row as i16)).abs();
-
         ensure!(
-            (coll_diff_abs == 1 && row_diff_abs == 2) || (coll_diff_abs == 2 && row_diff_abs == 1),
+            attacks::knight_attacks(src.index()).contains(dst.index()),
             InvalidMove
         );
         Ok(())
@@ -73,94 +32,95 @@ impl Board {
     }
 
     pub(crate) fn validate_bishop(&self, src: &Square, dst: &Square) -> Result<(), MoveError> {
-        let col_diff = usize::from(dst.col) as i32 - usize::from(src.col) as i32;
-        let col_diff_abs = col_diff.abs();
-
-        let row_diff = dst.row as i32 - src.row as i32;
-        let row_diff_abs = row_diff.abs();
-
         // Validate we're actually moving in a straight diagonal.
-        ensure!(col_diff_abs == row_diff_abs, InvalidMove);
-
-        // Validate the line is clear.
-        let direction_col = col_diff / col_diff_abs;
-        let direction_row = row_diff / row_diff_abs;
-        self.validate_line_clear(&src, dst, direction_col, direction_row)?;
+        ensure!(
+            attacks::bishop_attacks(src.index(), Bitboard::EMPTY).contains(dst.index()),
+            InvalidMove
+        );
 
-        Ok(())
+        self.validate_line_clear(src, dst)
     }
 
     pub(crate) fn validate_king(&self, src: &Square, dst: &Square) -> Result<(), MoveError> {
-        let coll_diff = usize::from(dst.col) as i32 - usize::from(src.col) as i32;
-        let coll_diff_abs = coll_diff.abs();
-        let row_diff_abs = ((src.row as i16) - (dst.row as i16)).abs();
-
-        if coll_diff_abs == 2 {
-            // Validate castling.
-            ensure!(row_diff_abs == 0, InvalidMove); // Don't allow vertical movements when castling.
-
-            // Get the square of the right rook.
-            let castle_state = self.get_castle_state(self.at(src).unwrap().color);
-            let (attempted_castle, rook_square) = {
-                if coll_diff > 0 {
-                    (CastleState::Kingside, Square::new(Column::H, src.row))
-                } else {
-                    (CastleState::Queenside, Square::new(Column::A, src.row))
-                }
-            };
-
-            ensure!(
-                castle_state == CastleState::Both || castle_state == attempted_castle,
-                CannotCastle
-            );
-
-            // Make sure the line is clear.
-            let coll_delta = coll_diff / coll_diff.abs();
-            self.validate_line_clear(src, &rook_square, coll_delta, 0)?;
-
-            self.validate_line_threat(
-                &src,
-                &rook_square,
-                coll_delta,
-                0,
-                self.at(src).unwrap().color.opposite(),
-            )?;
+        if let Some(side) = self.castling_side(src, dst) {
+            let color = self.at(src).unwrap().color;
+            self.validate_castling(color, side, src.index())?;
         } else {
             // Validate that the king can move one space in any direction.
-            ensure!(coll_diff_abs <= 1 && row_diff_abs <= 1, InvalidMove);
+            ensure!(
+                attacks::king_attacks(src.index()).contains(dst.index()),
+                InvalidMove
+            );
         }
 
         Ok(())
     }
 
-    pub(crate) fn validate_rook(&self, src: &Square, dst: &Square) -> Result<(), MoveError> {
-        let col_diff = usize::from(dst.col) as i32 - usize::from(src.col) as i32;
-        let coll_diff_abs = col_diff.abs();
+    /// Validates that the king on a given square can castle on a given side.
+    ///
+    /// The king and rook can start on any file of their row, as in Chess960. Every square either
+    /// of them moves through or to must be empty, other than their own, and the king can't be in
+    /// check or go through an attacked square.
+    pub(crate) fn validate_castling(
+        &self,
+        color: Color,
+        side: CastleState,
+        king_src: usize,
+    ) -> Result<Castling, MoveError> {
+        ensure!(self.get_castle_state(color).allows(side), CannotCastle);
+
+        let castling = self.castling(color, side, king_src);
+        ensure!(king_src / BOARD_DIMENSION == home_row(color), CannotCastle);
+        ensure!(
+            self.piece_at(castling.rook_src) == Some(Piece::new(PieceType::Rook, color)),
+            CannotCastle
+        );
 
-        let row_diff = dst.row as i32 - src.row as i32;
-        let row_diff_abs = row_diff.abs();
+        // The rook has to be on the side the king castles towards.
+        let rook_on_side = if side == CastleState::Kingside {
+            castling.rook_src > king_src
+        } else {
+            castling.rook_src < king_src
+        };
+        ensure!(rook_on_side, CannotCastle);
+
+        // Make sure the line is clear.
+        let king_path =
+            attacks::between(king_src, castling.king_dst) | Bitboard::from_index(castling.king_dst);
+        let rook_path = attacks::between(castling.rook_src, castling.rook_dst)
+            | Bitboard::from_index(castling.rook_dst);
+        let castlers = Bitboard::from_index(king_src) | Bitboard::from_index(castling.rook_src);
+        ensure!(
+            ((king_path | rook_path) & self.occupied() & !castlers).is_empty(),
+            PathObstructed
+        );
 
-        // Validate that movement is in a straight line.
-        ensure!(coll_diff_abs == 0 || row_diff_abs == 0, InvalidMove);
+        // Only the squares the king goes through need to be safe. The castling rook doesn't
+        // shield them, since it moves as well.
+        let by_color = color.opposite();
+        ensure!(
+            !self.validate_square_threatened(king_src, by_color),
+            CannotCastle
+        );
+        let occupied = self.occupied() & !Bitboard::from_index(castling.rook_src);
+        for index in king_path {
+            ensure!(
+                (self.attackers_to(index, occupied) & self.colors[by_color.index()]).is_empty(),
+                CannotCastleThroughCheck
+            );
+        }
 
-        // Validate the line is clear.
-        let direction_col = {
-            if coll_diff_abs == 0 {
-                0
-            } else {
-                col_diff / coll_diff_abs
-            }
-        };
-        let direction_row = {
-            if row_diff_abs == 0 {
-                0
-            } else {
-                row_diff / row_diff_abs
-            }
-        };
-        self.validate_line_clear(&src, &dst, direction_col, direction_row)?;
+        Ok(castling)
+    }
 
-        Ok(())
+    pub(crate) fn validate_rook(&self, src: &Square, dst: &Square) -> Result<(), MoveError> {
+        // Validate that movement is in a straight line.
+        ensure!(
+            attacks::rook_attacks(src.index(), Bitboard::EMPTY).contains(dst.index()),
+            InvalidMove
+        );
+
+        self.validate_line_clear(src, dst)
     }
 
     pub(crate) fn validate_pawn(
@@ -168,15 +128,16 @@ impl Board {
         src: &Square,
         dst: &Square,
         piece: &Piece,
+        promotion: Option<PieceType>,
     ) -> Result<(), MoveError> {
-        let pawn_did_move = src.row
+        let pawn_did_move = src.row()
             != match piece.color {
                 Color::Black => BLACK_PAWN_ROW,
                 Color::White => WHITE_PAWN_ROW,
             };
 
         let row_diff_rel = {
-            let diff = dst.row as i16 - src.row as i16;
+            let diff = dst.row() as i16 - src.row() as i16;
 
             // Make sure pawns can only move in one direction.
             if piece.color == Color::Black {
@@ -189,7 +150,7 @@ impl Board {
         };
         let row_diff = row_diff_rel.abs();
 
-        let coll_diff_abs = (usize::from(src.col) as i16 - usize::from(dst.col) as i16).abs();
+        let coll_diff_abs = (usize::from(src.col()) as i16 - usize::from(dst.col()) as i16).abs();
         let target_piece_maybe = self.at(dst);
 
         if coll_diff_abs > 0 {
@@ -198,21 +159,13 @@ impl Board {
             // Move must be a single diagonal.
             ensure!(coll_diff_abs == 1 && row_diff == 1, InvalidMove);
 
-            if let Some(en_passant_square) = self.en_passant_square.as_ref() {
-                // En passant square must be one row behind dst square.
-                let invert_row_diff = -row_diff_rel;
-                ensure!(
-                    &dst.clone().relative(0, invert_row_diff as i32).unwrap() == en_passant_square,
-                    InvalidMove
-                );
-
-                // Target square must be empty.
-                ensure!(target_piece_maybe.is_none(), InvalidMove);
-            } else {
+            if let Some(target_piece) = target_piece_maybe {
                 // There has to be a piece of the opposite color on the target square.
+                ensure!(target_piece.color != piece.color, InvalidMove);
+            } else {
+                // Capturing onto an empty square is only allowed en passant, by the side to play.
                 ensure!(
-                    target_piece_maybe.is_some()
-                        && target_piece_maybe.unwrap().color != piece.color,
+                    self.en_passant_square.as_ref() == Some(dst) && piece.color == self.to_play,
                     InvalidMove
                 );
             }
@@ -224,10 +177,31 @@ impl Board {
                 ensure!((row_diff == 1 || row_diff == 2), InvalidMove);
             }
 
+            // Pawns can't jump over pieces on their first move.
+            if row_diff == 2 {
+                let skipped_square = src.relative(0, (row_diff_rel / 2) as i32).unwrap();
+                ensure!(self.at(&skipped_square).is_none(), PathObstructed);
+            }
+
             // Validate there is no piece on the target square.
             ensure!(target_piece_maybe.is_none(), InvalidMove);
         }
 
+        // Validate promotion.
+        let last_row = match piece.color {
+            Color::Black => WHITE_PIECES_ROW,
+            Color::White => BLACK_PIECES_ROW,
+        };
+        if dst.row() == last_row {
+            let promotion_type = promotion.context(PromotionRequired)?;
+            ensure!(
+                promotion_type != PieceType::Pawn && promotion_type != PieceType::King,
+                InvalidPromotion
+            );
+        } else {
+            ensure!(promotion.is_none(), InvalidPromotion);
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,237 @@
+//! Static evaluation of positions.
+//!
+//! Each piece is worth its material value plus a bonus for its square, looked up in a
+//! middlegame and an endgame table. The two scores are blended by how much material is left on
+//! the board, so that the king is kept safe while the queens are on but walks up in the endgame.
+//!
+//! Values and tables are those of the PeSTO evaluation function.
+
+use crate::constants::*;
+use crate::{Board, Color, PieceType};
+
+/// Material values in the middlegame and endgame, indexed by `PieceType::index`.
+const MIDDLEGAME_VALUES: [i32; 6] = [82, 365, 337, 477, 1025, 0];
+const ENDGAME_VALUES: [i32; 6] = [94, 297, 281, 512, 936, 0];
+
+/// How much each piece counts towards the game phase, indexed by `PieceType::index`.
+const PHASE_WEIGHTS: [i32; 6] = [0, 1, 1, 2, 4, 0];
+
+/// The phase with every piece of the starting position on the board.
+const MIDDLEGAME_PHASE: i32 = 24;
+
+// Piece-square tables, from White's point of view. They read like a board diagram, with the
+// eighth row first, so a white piece's square has to be flipped vertically to look it up.
+#[rustfmt::skip]
+const MIDDLEGAME_PAWN: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     98, 134,  61,  95,  68, 126,  34, -11,
+     -6,   7,  26,  31,  65,  56,  25, -20,
+    -14,  13,   6,  21,  23,  12,  17, -23,
+    -27,  -2,  -5,  12,  17,   6,  10, -25,
+    -26,  -4,  -4, -10,   3,   3,  33, -12,
+    -35,  -1, -20, -23, -15,  24,  38, -22,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const ENDGAME_PAWN: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+    178, 173, 158, 134, 147, 132, 165, 187,
+     94, 100,  85,  67,  56,  53,  82,  84,
+     32,  24,  13,   5,  -2,   4,  17,  17,
+     13,   9,  -3,  -7,  -7,  -8,   3,  -1,
+      4,   7,  -6,   1,   0,  -5,  -1,  -8,
+     13,   8,   8,  10,  13,   0,   2,  -7,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const MIDDLEGAME_KNIGHT: [i32; 64] = [
+    -167, -89, -34, -49,  61, -97, -15, -107,
+     -73, -41,  72,  36,  23,  62,   7,  -17,
+     -47,  60,  37,  65,  84, 129,  73,   44,
+      -9,  17,  19,  53,  37,  69,  18,   22,
+     -13,   4,  16,  13,  28,  19,  21,   -8,
+     -23,  -9,  12,  10,  19,  17,  25,  -16,
+     -29, -53, -12,  -3,  -1,  18, -14,  -19,
+    -105, -21, -58, -33, -17, -28, -19,  -23,
+];
+
+#[rustfmt::skip]
+const ENDGAME_KNIGHT: [i32; 64] = [
+    -58, -38, -13, -28, -31, -27, -63, -99,
+    -25,  -8, -25,  -2,  -9, -25, -24, -52,
+    -24, -20,  10,   9,  -1,  -9, -19, -41,
+    -17,   3,  22,  22,  22,  11,   8, -18,
+    -18,  -6,  16,  25,  16,  17,   4, -18,
+    -23,  -3,  -1,  15,  10,  -3, -20, -22,
+    -42, -20, -10,  -5,  -2, -20, -23, -44,
+    -29, -51, -23, -15, -22, -18, -50, -64,
+];
+
+#[rustfmt::skip]
+const MIDDLEGAME_BISHOP: [i32; 64] = [
+    -29,   4, -82, -37, -25, -42,   7,  -8,
+    -26,  16, -18, -13,  30,  59,  18, -47,
+    -16,  37,  43,  40,  35,  50,  37,  -2,
+     -4,   5,  19,  50,  37,  37,   7,  -2,
+     -6,  13,  13,  26,  34,  12,  10,   4,
+      0,  15,  15,  15,  14,  27,  18,  10,
+      4,  15,  16,   0,   7,  21,  33,   1,
+    -33,  -3, -14, -21, -13, -12, -39, -21,
+];
+
+#[rustfmt::skip]
+const ENDGAME_BISHOP: [i32; 64] = [
+    -14, -21, -11,  -8,  -7,  -9, -17, -24,
+     -8,  -4,   7, -12,  -3, -13,  -4, -14,
+      2,  -8,   0,  -1,  -2,   6,   0,   4,
+     -3,   9,  12,   9,  14,  10,   3,   2,
+     -6,   3,  13,  19,   7,  10,  -3,  -9,
+    -12,  -3,   8,  10,  13,   3,  -7, -15,
+    -14, -18,  -7,  -1,   4,  -9, -15, -27,
+    -23,  -9, -23,  -5,  -9, -16,  -5, -17,
+];
+
+#[rustfmt::skip]
+const MIDDLEGAME_ROOK: [i32; 64] = [
+     32,  42,  32,  51,  63,   9,  31,  43,
+     27,  32,  58,  62,  80,  67,  26,  44,
+     -5,  19,  26,  36,  17,  45,  61,  16,
+    -24, -11,   7,  26,  24,  35,  -8, -20,
+    -36, -26, -12,  -1,   9,  -7,   6, -23,
+    -45, -25, -16, -17,   3,   0,  -5, -33,
+    -44, -16, -20,  -9,  -1,  11,  -6, -71,
+    -19, -13,   1,  17,  16,   7, -37, -26,
+];
+
+#[rustfmt::skip]
+const ENDGAME_ROOK: [i32; 64] = [
+     13,  10,  18,  15,  12,  12,   8,   5,
+     11,  13,  13,  11,  -3,   3,   8,   3,
+      7,   7,   7,   5,   4,  -3,  -5,  -3,
+      4,   3,  13,   1,   2,   1,  -1,   2,
+      3,   5,   8,   4,  -5,  -6,  -8, -11,
+     -4,   0,  -5,  -1,  -7, -12,  -8, -16,
+     -6,  -6,   0,   2,  -9,  -9, -11,  -3,
+     -9,   2,   3,  -1,  -5, -13,   4, -20,
+];
+
+#[rustfmt::skip]
+const MIDDLEGAME_QUEEN: [i32; 64] = [
+    -28,   0,  29,  12,  59,  44,  43,  45,
+    -24, -39,  -5,   1, -16,  57,  28,  54,
+    -13, -17,   7,   8,  29,  56,  47,  57,
+    -27, -27, -16, -16,  -1,  17,  -2,   1,
+     -9, -26,  -9, -10,  -2,  -4,   3,  -3,
+    -14,   2, -11,  -2,  -5,   2,  14,   5,
+    -35,  -8,  11,   2,   8,  15,  -3,   1,
+     -1, -18,  -9,  10, -15, -25, -31, -50,
+];
+
+#[rustfmt::skip]
+const ENDGAME_QUEEN: [i32; 64] = [
+     -9,  22,  22,  27,  27,  19,  10,  20,
+    -17,  20,  32,  41,  58,  25,  30,   0,
+    -20,   6,   9,  49,  47,  35,  19,   9,
+      3,  22,  24,  45,  57,  40,  57,  36,
+    -18,  28,  19,  47,  31,  34,  39,  23,
+    -16, -27,  15,   6,   9,  17,  10,   5,
+    -22, -23, -30, -16, -16, -23, -36, -32,
+    -33, -28, -22, -43,  -5, -32, -20, -41,
+];
+
+#[rustfmt::skip]
+const MIDDLEGAME_KING: [i32; 64] = [
+    -65,  23,  16, -15, -56, -34,   2,  13,
+     29,  -1, -20,  -7,  -8,  -4, -38, -29,
+     -9,  24,   2, -16, -20,   6,  22, -22,
+    -17, -20, -12, -27, -30, -25, -14, -36,
+    -49,  -1, -27, -39, -46, -44, -33, -51,
+    -14, -14, -22, -46, -44, -30, -15, -27,
+      1,   7,  -8, -64, -43, -16,   9,   8,
+    -15,  36,  12, -54,   8, -28,  24,  14,
+];
+
+#[rustfmt::skip]
+const ENDGAME_KING: [i32; 64] = [
+    -74, -35, -18, -18, -11,  15,   4, -17,
+    -12,  17,  14,  17,  17,  38,  23,  11,
+     10,  17,  23,  15,  20,  45,  44,  13,
+     -8,  22,  24,  27,  26,  33,  26,   3,
+    -18,  -4,  21,  24,  27,  23,   9, -11,
+    -19,  -3,  11,  21,  23,  16,   7,  -9,
+    -27, -11,   4,  13,  14,   4,  -5, -17,
+    -53, -34, -21, -11, -28, -14, -24, -43,
+];
+
+/// Piece-square tables in the middlegame and endgame, indexed by `PieceType::index`.
+const MIDDLEGAME_TABLES: [&[i32; 64]; 6] = [
+    &MIDDLEGAME_PAWN,
+    &MIDDLEGAME_BISHOP,
+    &MIDDLEGAME_KNIGHT,
+    &MIDDLEGAME_ROOK,
+    &MIDDLEGAME_QUEEN,
+    &MIDDLEGAME_KING,
+];
+const ENDGAME_TABLES: [&[i32; 64]; 6] = [
+    &ENDGAME_PAWN,
+    &ENDGAME_BISHOP,
+    &ENDGAME_KNIGHT,
+    &ENDGAME_ROOK,
+    &ENDGAME_QUEEN,
+    &ENDGAME_KING,
+];
+
+const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+/// Returns the index of a square in the tables, which are laid out from Black's side.
+fn table_index(index: usize, color: Color) -> usize {
+    match color {
+        // Flip the row, keeping the column.
+        Color::White => index ^ (BOARD_DIMENSION * (BOARD_DIMENSION - 1)),
+        Color::Black => index,
+    }
+}
+
+impl Board {
+    /// Evaluates the position statically, in centipawns from the point of view of the side to
+    /// play.
+    ///
+    /// The score only looks at the pieces on the board: it doesn't know about checkmate, draws
+    /// or pieces hanging. Flipping the board and swapping the colors gives the same score.
+    pub fn evaluate(&self) -> i32 {
+        let mut middlegame = [0; 2];
+        let mut endgame = [0; 2];
+        let mut phase = 0;
+
+        for color in &[Color::White, Color::Black] {
+            for piece_type in PIECE_TYPES.iter() {
+                let piece = piece_type.index();
+                for index in self.pieces_of(*piece_type, *color) {
+                    let square = table_index(index, *color);
+                    middlegame[color.index()] +=
+                        MIDDLEGAME_VALUES[piece] + MIDDLEGAME_TABLES[piece][square];
+                    endgame[color.index()] += ENDGAME_VALUES[piece] + ENDGAME_TABLES[piece][square];
+                    phase += PHASE_WEIGHTS[piece];
+                }
+            }
+        }
+
+        // Promotions can bring the phase over that of the starting position.
+        let phase = phase.min(MIDDLEGAME_PHASE);
+
+        let us = self.to_play.index();
+        let them = self.to_play.opposite().index();
+        let middlegame_score = middlegame[us] - middlegame[them];
+        let endgame_score = endgame[us] - endgame[them];
+        (middlegame_score * phase + endgame_score * (MIDDLEGAME_PHASE - phase)) / MIDDLEGAME_PHASE
+    }
+}
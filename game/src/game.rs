@@ -0,0 +1,165 @@
+use std::iter;
+
+use crate::{setup::board_with_setup, Board, DrawReason, GameStatus, Move, MoveError};
+
+type Result<T> = std::result::Result<T, MoveError>;
+
+/// A move played during a game.
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    /// The move played, with its details (moved piece, captured piece...) filled in.
+    pub mv: Move,
+
+    /// The position reached after the move.
+    pub position: Board,
+}
+
+/// A game of chess, keeping track of every move played since the starting position.
+#[derive(Clone, Debug)]
+pub struct Game {
+    starting_position: Board,
+    history: Vec<HistoryEntry>,
+
+    /// Number of history entries currently played. Entries past this point were undone and can
+    /// be redone.
+    current: usize,
+
+    /// A draw claimed in the current position.
+    claimed_draw: Option<DrawReason>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::from_position(board_with_setup())
+    }
+}
+
+impl Game {
+    /// Starts a new game from the standard starting position.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new game from an arbitrary position.
+    pub fn from_position(starting_position: Board) -> Self {
+        Self {
+            starting_position,
+            history: Vec::new(),
+            current: 0,
+            claimed_draw: None,
+        }
+    }
+
+    /// The position the game started from.
+    pub fn starting_position(&self) -> &Board {
+        &self.starting_position
+    }
+
+    /// The current position of the game.
+    pub fn position(&self) -> &Board {
+        match self.current {
+            0 => &self.starting_position,
+            n => &self.history[n - 1].position,
+        }
+    }
+
+    /// The status of the game in its current position, including draws by repetition and
+    /// claimed draws.
+    pub fn status(&self) -> GameStatus {
+        if let Some(reason) = self.claimed_draw {
+            return GameStatus::Draw { reason };
+        }
+
+        let status = self.position().status();
+        if !status.is_over() && self.repetitions() >= 5 {
+            return GameStatus::Draw {
+                reason: DrawReason::FivefoldRepetition,
+            };
+        }
+
+        status
+    }
+
+    /// Number of times the current position occurred in the game, counting the current one.
+    ///
+    /// Positions are the same when they have the same pieces on the same squares, the same side
+    /// to play, the same castling rights and the same en-passant captures available.
+    pub fn repetitions(&self) -> usize {
+        let hash = self.position().hash();
+        self.positions().filter(|p| p.hash() == hash).count()
+    }
+
+    /// Returns the draw the side to play could claim in the current position, if any.
+    pub fn claimable_draw(&self) -> Option<DrawReason> {
+        if self.status().is_over() {
+            return None;
+        }
+
+        if self.repetitions() >= 3 {
+            return Some(DrawReason::ThreefoldRepetition);
+        }
+
+        self.position().claimable_draw()
+    }
+
+    /// Claims a draw in the current position, ending the game.
+    ///
+    /// Returns the reason of the draw, or `None` if no draw can be claimed. Playing or taking
+    /// back a move cancels the claim.
+    pub fn claim_draw(&mut self) -> Option<DrawReason> {
+        let reason = self.claimable_draw()?;
+        self.claimed_draw = Some(reason);
+        Some(reason)
+    }
+
+    /// Plays a move from the current position.
+    ///
+    /// Any undone moves are discarded.
+    pub fn play(&mut self, mv: &Move) -> Result<&HistoryEntry> {
+        let (position, mv) = self.position().move_piece(mv)?;
+
+        self.claimed_draw = None;
+        self.history.truncate(self.current);
+        self.history.push(HistoryEntry { mv, position });
+        self.current += 1;
+
+        Ok(&self.history[self.current - 1])
+    }
+
+    /// Takes back the last move played, returning it.
+    pub fn undo(&mut self) -> Option<&HistoryEntry> {
+        if self.current == 0 {
+            return None;
+        }
+
+        self.current -= 1;
+        self.claimed_draw = None;
+        Some(&self.history[self.current])
+    }
+
+    /// Plays back the last move that was undone, returning it.
+    pub fn redo(&mut self) -> Option<&HistoryEntry> {
+        if self.current == self.history.len() {
+            return None;
+        }
+
+        self.current += 1;
+        self.claimed_draw = None;
+        Some(&self.history[self.current - 1])
+    }
+
+    /// The moves played so far, along with the positions they led to.
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history[..self.current]
+    }
+
+    /// The moves played so far.
+    pub fn moves(&self) -> impl Iterator<Item = &Move> {
+        self.history().iter().map(|entry| &entry.mv)
+    }
+
+    /// Every position reached so far, starting with the starting position.
+    pub fn positions(&self) -> impl Iterator<Item = &Board> {
+        iter::once(&self.starting_position).chain(self.history().iter().map(|e| &e.position))
+    }
+}
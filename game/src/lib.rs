@@ -1,18 +1,49 @@
+mod attacks;
+mod bitboard;
 mod board;
+mod board_attacks;
 mod board_fen;
+mod board_make;
+mod board_movegen;
+mod board_perft;
+mod board_san;
+mod board_see;
+mod board_status;
 mod board_validation;
 
 pub mod constants;
+mod eval;
+mod game;
+mod move_picker;
+mod moves;
+mod pgn;
 mod pieces;
+mod prng;
+mod search;
+#[cfg(feature = "serde")]
+mod serialization;
 mod setup;
 mod square;
+mod transposition;
+mod zobrist;
 
 pub use board::Board;
+pub use board_attacks::Pin;
+pub use board_fen::{FenError, FenMode};
+pub use board_make::UndoInfo;
+pub use board_san::SanError;
+pub use board_status::{DrawReason, GameStatus};
+pub use game::{Game, HistoryEntry};
+pub use move_picker::OrderingStats;
+pub use moves::{Move, MoveKind, NULL_MOVE_UCI};
+pub use pgn::{read_pgn, GameResult, PgnError, PgnGame};
 pub use pieces::{Color, Piece, PieceType};
+pub use search::{Score, Search, SearchLimits, SearchOptions, SearchResult, MAX_DEPTH};
 pub use square::{Column, Row, Square};
+pub use transposition::{TableStats, DEFAULT_TABLE_SIZE_MB};
 
-use square::Error as NotationError;
+pub use square::Error as NotationError;
 
 pub use board::MoveError;
 
-pub use setup::board_with_setup;
+pub use setup::{board_with_chess960_setup, board_with_setup};
@@ -0,0 +1,221 @@
+//! Move ordering for the search.
+//!
+//! Alpha-beta prunes the most when the best move is searched first. The picker hands out the
+//! moves of a position in stages, from the most to the least promising: the best move stored
+//! for the position, captures winning material, promotions, quiet moves that refuted other
+//! positions at the same ply (killers) or the opponent's last move (countermoves), the other
+//! quiet moves by how often they refuted positions before (history), and captures losing
+//! material last.
+
+use crate::board_see::SEE_VALUES;
+use crate::constants::BOARD_DIMENSION;
+use crate::{Board, Color, Move};
+
+const SQUARE_COUNT: usize = BOARD_DIMENSION * BOARD_DIMENSION;
+
+/// History scores stay within this, positive or negative. Updates are damped as a score gets
+/// close to it, so that recent refutations weigh more than old ones.
+const MAX_HISTORY: i32 = 1 << 14;
+
+/// Statistics about move ordering during a search.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OrderingStats {
+    /// The number of positions where a move refuted the opponent's last move.
+    pub cutoffs: u64,
+
+    /// The number of those where the refutation was the first move searched.
+    pub first_move_cutoffs: u64,
+}
+
+impl OrderingStats {
+    /// The share of refutations found by the first move searched, between 0 and 1.
+    pub fn first_move_cutoff_rate(&self) -> f64 {
+        if self.cutoffs == 0 {
+            0.0
+        } else {
+            self.first_move_cutoffs as f64 / self.cutoffs as f64
+        }
+    }
+}
+
+/// The stages of the picker, in the order their moves are handed out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Stage {
+    HashMove,
+    GoodCapture,
+    Promotion,
+    Killer,
+    Countermove,
+    Quiet,
+    BadCapture,
+}
+
+/// Ranks captures by the value of the piece taken, then by the value of the piece taking it,
+/// lowest first.
+pub(crate) fn mvv_lva(mv: &Move) -> i32 {
+    let victim = mv.captured.map(|t| SEE_VALUES[t.index()]).unwrap_or(0);
+    let attacker = mv.piece.map(|t| SEE_VALUES[t.index()]).unwrap_or(0);
+    victim * 64 - attacker / 100
+}
+
+/// What the search learned about quiet moves, used to order them.
+pub(crate) struct OrderingTables {
+    /// Two quiet moves per ply that refuted a position at that ply.
+    killers: Vec<[Option<Move>; 2]>,
+
+    /// Indexed by color, source square then destination square.
+    history: Vec<[[i32; SQUARE_COUNT]; SQUARE_COUNT]>,
+
+    /// The quiet move that last refuted a move, indexed by the color, piece type and
+    /// destination square of the refuted move, see `countermove_index`.
+    countermoves: Vec<Option<Move>>,
+}
+
+/// Adds a bonus, or a malus, to a history score. The closer the score already is to the bound
+/// in that direction, the less it moves, so that it never goes past it.
+fn update_history(score: &mut i32, bonus: i32) {
+    *score += bonus - *score * bonus.abs() / MAX_HISTORY;
+}
+
+fn countermove_index(color: Color, previous: &Move) -> Option<usize> {
+    let piece = previous.piece?;
+    Some((color.index() * 6 + piece.index()) * SQUARE_COUNT + previous.dst.index())
+}
+
+impl OrderingTables {
+    pub fn new(max_ply: usize) -> Self {
+        OrderingTables {
+            killers: vec![[None, None]; max_ply + 1],
+            history: vec![[[0; SQUARE_COUNT]; SQUARE_COUNT]; 2],
+            countermoves: vec![None; 2 * 6 * SQUARE_COUNT],
+        }
+    }
+
+    /// Prepares for a new search. Killers are forgotten, as plies now count from another root,
+    /// and history scores are halved.
+    pub fn new_search(&mut self) {
+        for killers in self.killers.iter_mut() {
+            *killers = [None, None];
+        }
+        for score in self.history.iter_mut().flatten().flatten() {
+            *score /= 2;
+        }
+    }
+
+    /// Returns the quiet move that last refuted a move, played by a given color.
+    fn countermove(&self, color: Color, previous: &Move) -> Option<&Move> {
+        self.countermoves[countermove_index(color, previous)?].as_ref()
+    }
+
+    fn history(&self, color: Color, mv: &Move) -> i32 {
+        self.history[color.index()][mv.src.index()][mv.dst.index()]
+    }
+
+    /// Records that a quiet move refuted a position, after the other quiet moves `tried` failed
+    /// to.
+    pub fn record_refutation(
+        &mut self,
+        color: Color,
+        ply: usize,
+        depth: usize,
+        mv: &Move,
+        previous: Option<&Move>,
+        tried: &[Move],
+    ) {
+        let killers = &mut self.killers[ply];
+        if killers[0].as_ref() != Some(mv) {
+            killers[1] = killers[0].take();
+            killers[0] = Some(mv.clone());
+        }
+
+        if let Some(index) =
+            previous.and_then(|previous| countermove_index(color.opposite(), previous))
+        {
+            self.countermoves[index] = Some(mv.clone());
+        }
+
+        // Deeper refutations are more reliable.
+        let bonus = ((depth * depth) as i32).min(MAX_HISTORY);
+        let history = &mut self.history[color.index()];
+        update_history(&mut history[mv.src.index()][mv.dst.index()], bonus);
+        for tried in tried {
+            update_history(&mut history[tried.src.index()][tried.dst.index()], -bonus);
+        }
+    }
+}
+
+/// Hands out the moves of a position in the order they should be searched.
+///
+/// The moves of each stage are picked one at a time, the best remaining first, so that no time
+/// is spent ordering the moves that won't be searched after a refutation. Captures are only
+/// told apart by static exchange evaluation once picked, moving those losing material to the
+/// last stage.
+pub(crate) struct MovePicker {
+    moves: Vec<(Move, Stage, i32)>,
+    next: usize,
+}
+
+impl MovePicker {
+    /// Orders the legal moves of a position, `ply` plies away from the root, after the move
+    /// `previous`.
+    pub fn new(
+        board: &Board,
+        moves: Vec<Move>,
+        hash_move: Option<&Move>,
+        tables: &OrderingTables,
+        ply: usize,
+        previous: Option<&Move>,
+    ) -> Self {
+        let color = board.to_play;
+        let killers = &tables.killers[ply];
+        let countermove =
+            previous.and_then(|previous| tables.countermove(color.opposite(), previous));
+
+        let moves = moves
+            .into_iter()
+            .map(|mv| {
+                let (stage, score) = if Some(&mv) == hash_move {
+                    (Stage::HashMove, 0)
+                } else if mv.is_capture() {
+                    (Stage::GoodCapture, mvv_lva(&mv))
+                } else if let Some(promotion) = mv.promotion {
+                    (Stage::Promotion, SEE_VALUES[promotion.index()])
+                } else if let Some(slot) = killers
+                    .iter()
+                    .position(|killer| killer.as_ref() == Some(&mv))
+                {
+                    (Stage::Killer, -(slot as i32))
+                } else if Some(&mv) == countermove {
+                    (Stage::Countermove, 0)
+                } else {
+                    (Stage::Quiet, tables.history(color, &mv))
+                };
+                (mv, stage, score)
+            })
+            .collect();
+
+        MovePicker { moves, next: 0 }
+    }
+
+    /// Returns the next move to search, `board` being the position the picker was created for.
+    pub fn next_move(&mut self, board: &Board) -> Option<Move> {
+        loop {
+            let remaining = &mut self.moves[self.next..];
+            let best = remaining
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (_, stage, score))| (*stage, -score))
+                .map(|(i, _)| i)?;
+            remaining.swap(0, best);
+
+            let (mv, stage, _) = &mut remaining[0];
+            if *stage == Stage::GoodCapture && board.see(mv) < 0 {
+                *stage = Stage::BadCapture;
+                continue;
+            }
+
+            self.next += 1;
+            return Some(mv.clone());
+        }
+    }
+}
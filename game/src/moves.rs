@@ -0,0 +1,138 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::board::{InvalidPromotion, InvalidSquare, MoveError};
+use crate::square::{InvalidMoveLength, InvalidPromotionPiece};
+use crate::{NotationError, PieceType, Square};
+
+/// The null move ("pass") in UCI notation.
+pub const NULL_MOVE_UCI: &str = "0000";
+
+/// The special rules a move goes through, if any.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MoveKind {
+    /// A regular move or capture.
+    Normal,
+    DoublePawnPush,
+    EnPassant,
+    KingsideCastle,
+    QueensideCastle,
+}
+
+/// A move of a piece from one square to another.
+///
+/// A move is described by its source & destination squares and its promotion piece. The
+/// remaining details (which piece moves, what it captures and the kind of move) depend on the
+/// position, and are filled in by `Board::validate_move` and `Board::move_piece`.
+#[derive(Clone, Debug)]
+pub struct Move {
+    pub src: Square,
+    pub dst: Square,
+
+    /// The piece a pawn reaching the last rank is promoted to.
+    pub promotion: Option<PieceType>,
+
+    /// The piece being moved.
+    pub piece: Option<PieceType>,
+
+    /// The piece taken by this move, if any.
+    pub captured: Option<PieceType>,
+
+    pub kind: MoveKind,
+}
+
+impl PartialEq for Move {
+    /// Two moves are equal if they describe the same move, whether or not their details were
+    /// filled in.
+    fn eq(&self, other: &Self) -> bool {
+        self.src == other.src && self.dst == other.dst && self.promotion == other.promotion
+    }
+}
+
+impl Move {
+    pub fn new(src: Square, dst: Square, promotion: Option<PieceType>) -> Self {
+        Self {
+            src,
+            dst,
+            promotion,
+            piece: None,
+            captured: None,
+            kind: MoveKind::Normal,
+        }
+    }
+
+    /// Builds a move from square notation (e.g. "e7" "e8"), with an optional promotion piece
+    /// (e.g. 'q' or 'n').
+    pub fn from_notation(src: &str, dst: &str, promotion: Option<char>) -> Result<Self, MoveError> {
+        let src_square = Square::from_notation(src).context(InvalidSquare)?;
+        let dst_square = Square::from_notation(dst).context(InvalidSquare)?;
+        let promotion_type = match promotion {
+            Some(ch) => Some(
+                PieceType::try_from(ch.to_string())
+                    .ok()
+                    .context(InvalidPromotion)?,
+            ),
+            None => None,
+        };
+        Ok(Move::new(src_square, dst_square, promotion_type))
+    }
+
+    /// Parses a move in UCI long algebraic notation (e.g. "e2e4", "e7e8q", or "e1g1" to
+    /// castle).
+    ///
+    /// Returns `None` for the null move.
+    pub fn from_uci(uci: &str) -> Result<Option<Self>, NotationError> {
+        if uci == NULL_MOVE_UCI {
+            return Ok(None);
+        }
+
+        ensure!(
+            uci.is_ascii() && (uci.len() == 4 || uci.len() == 5),
+            InvalidMoveLength
+        );
+
+        let src = Square::from_notation(&uci[0..2])?;
+        let dst = Square::from_notation(&uci[2..4])?;
+        let promotion = match uci.chars().nth(4) {
+            Some(ch) => {
+                let piece_type = PieceType::try_from(ch.to_string())
+                    .ok()
+                    .context(InvalidPromotionPiece)?;
+                ensure!(
+                    piece_type != PieceType::Pawn && piece_type != PieceType::King,
+                    InvalidPromotionPiece
+                );
+                Some(piece_type)
+            }
+            None => None,
+        };
+
+        Ok(Some(Move::new(src, dst, promotion)))
+    }
+
+    /// Formats the move in UCI long algebraic notation.
+    pub fn to_uci(&self) -> String {
+        let promotion = self.promotion.map(String::from).unwrap_or_default();
+        format!("{}{}{}", self.src, self.dst, promotion)
+    }
+
+    pub fn is_capture(&self) -> bool {
+        self.captured.is_some()
+    }
+
+    pub fn is_castle(&self) -> bool {
+        self.kind == MoveKind::KingsideCastle || self.kind == MoveKind::QueensideCastle
+    }
+
+    pub fn is_promotion(&self) -> bool {
+        self.promotion.is_some()
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_uci())
+    }
+}
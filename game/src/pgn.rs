@@ -0,0 +1,500 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use snafu::{ResultExt, Snafu};
+
+use crate::board_fen::FenError;
+use crate::{setup::board_with_setup, Board, Color, Game, GameStatus, SanError};
+
+/// Maximum length of a movetext line when writing PGN.
+const MAX_LINE_LENGTH: usize = 80;
+
+/// The tags every PGN game must have, in the order they must be written.
+const SEVEN_TAG_ROSTER: &[(&str, &str)] = &[
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+#[derive(Debug, Snafu)]
+pub enum PgnError {
+    #[snafu(display("{}:{}: Unterminated comment.", line, column))]
+    UnterminatedComment { line: usize, column: usize },
+
+    #[snafu(display("{}:{}: Invalid tag.", line, column))]
+    InvalidTag { line: usize, column: usize },
+
+    #[snafu(display("{}:{}: Unbalanced variation.", line, column))]
+    UnbalancedVariation { line: usize, column: usize },
+
+    #[snafu(display("{}:{}: Invalid FEN tag: {:?}", line, column, source))]
+    InvalidFenTag {
+        line: usize,
+        column: usize,
+        source: FenError,
+    },
+
+    #[snafu(display("{}:{}: Illegal move \"{}\": {}", line, column, san, source))]
+    IllegalMove {
+        line: usize,
+        column: usize,
+        san: String,
+        source: SanError,
+    },
+}
+
+type Result<T> = std::result::Result<T, PgnError>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    /// The game is still in progress, or its result is unknown.
+    Unknown,
+}
+
+impl GameResult {
+    fn from_pgn(token: &str) -> Option<GameResult> {
+        match token {
+            "1-0" => Some(GameResult::WhiteWins),
+            "0-1" => Some(GameResult::BlackWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            "*" => Some(GameResult::Unknown),
+            _ => None,
+        }
+    }
+}
+
+impl From<GameStatus> for GameResult {
+    fn from(status: GameStatus) -> Self {
+        match status {
+            GameStatus::Ongoing => GameResult::Unknown,
+            GameStatus::Checkmate {
+                winner: Color::White,
+            } => GameResult::WhiteWins,
+            GameStatus::Checkmate {
+                winner: Color::Black,
+            } => GameResult::BlackWins,
+            GameStatus::Stalemate | GameStatus::Draw { .. } => GameResult::Draw,
+        }
+    }
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = match self {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Unknown => "*",
+        };
+
+        write!(f, "{}", result)
+    }
+}
+
+/// A game read from or written to PGN.
+#[derive(Clone, Debug)]
+pub struct PgnGame {
+    /// Tags in the order they were read, excluding the result which is kept in `result`.
+    pub tags: Vec<(String, String)>,
+    pub game: Game,
+    pub result: GameResult,
+}
+
+impl PgnGame {
+    /// Wraps a game, taking its result from the status of its current position.
+    pub fn new(game: Game) -> Self {
+        let result = GameResult::from(game.status());
+        Self {
+            tags: Vec::new(),
+            game,
+            result,
+        }
+    }
+
+    /// Returns the value of a tag, if set.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    /// Sets the value of a tag, replacing any previous value.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some(tag) => tag.1 = String::from(value),
+            None => self.tags.push((String::from(name), String::from(value))),
+        }
+    }
+
+    /// Formats the game as PGN.
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::default();
+
+        // Tags, starting with the seven tag roster.
+        let result = self.result.to_string();
+        for (name, default) in SEVEN_TAG_ROSTER {
+            let value = match *name {
+                "Result" => &result,
+                _ => self.tag(name).unwrap_or(default),
+            };
+            pgn += &format_tag(name, value);
+        }
+
+        let starting_position = self.game.starting_position();
+        let is_setup = starting_position.to_fen() != board_with_setup().to_fen();
+        if is_setup {
+            pgn += &format_tag("SetUp", "1");
+            pgn += &format_tag("FEN", &starting_position.to_fen());
+        }
+
+        for (name, value) in self.tags.iter() {
+            let is_roster = SEVEN_TAG_ROSTER.iter().any(|(n, _)| n == name);
+            if !is_roster && !(is_setup && (name == "SetUp" || name == "FEN")) {
+                pgn += &format_tag(name, value);
+            }
+        }
+        pgn += "\n";
+
+        // Movetext.
+        let mut tokens = Vec::new();
+        let mut move_number = starting_position.full_move_clock;
+        let mut to_play = starting_position.to_play;
+        for (i, (position, entry)) in self
+            .game
+            .positions()
+            .zip(self.game.history().iter())
+            .enumerate()
+        {
+            if to_play == Color::White {
+                tokens.push(format!("{}.", move_number));
+            } else if i == 0 {
+                tokens.push(format!("{}...", move_number));
+            }
+
+            // Moves in the history are always legal.
+            tokens.push(position.to_san(&entry.mv).unwrap());
+
+            if to_play == Color::Black {
+                move_number += 1;
+            }
+            to_play = to_play.opposite();
+        }
+        tokens.push(result);
+
+        let mut line = String::default();
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+                pgn += &line;
+                pgn += "\n";
+                line.clear();
+            }
+            if !line.is_empty() {
+                line += " ";
+            }
+            line += &token;
+        }
+        pgn += &line;
+        pgn += "\n";
+
+        pgn
+    }
+}
+
+fn format_tag(name: &str, value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("[{} \"{}\"]\n", name, escaped)
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Symbol(String),
+    Nag,
+    OpenVariation,
+    CloseVariation,
+}
+
+struct Tokenizer<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(ch) = self.next_char() {
+            if ch == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn read_tag(&mut self, line: usize, column: usize) -> Result<Token> {
+        let invalid = || InvalidTag { line, column };
+
+        let mut name = String::default();
+        while let Some(&ch) = self.chars.peek() {
+            if ch.is_whitespace() || ch == '"' {
+                break;
+            }
+            name.push(ch);
+            self.next_char();
+        }
+
+        while self
+            .chars
+            .peek()
+            .map(|c| c.is_whitespace())
+            .unwrap_or(false)
+        {
+            self.next_char();
+        }
+
+        if name.is_empty() || self.next_char() != Some('"') {
+            return invalid().fail();
+        }
+
+        let mut value = String::default();
+        loop {
+            match self.next_char() {
+                Some('\\') => match self.next_char() {
+                    Some(ch) => value.push(ch),
+                    None => return invalid().fail(),
+                },
+                Some('"') => break,
+                Some(ch) => value.push(ch),
+                None => return invalid().fail(),
+            }
+        }
+
+        while self
+            .chars
+            .peek()
+            .map(|c| c.is_whitespace())
+            .unwrap_or(false)
+        {
+            self.next_char();
+        }
+
+        if self.next_char() != Some(']') {
+            return invalid().fail();
+        }
+
+        Ok(Token::Tag(name, value))
+    }
+
+    /// Returns the next token along with the line and column it starts at.
+    fn next_token(&mut self) -> Result<Option<(Token, usize, usize)>> {
+        loop {
+            let (line, column) = (self.line, self.column);
+            let ch = match self.next_char() {
+                Some(ch) => ch,
+                None => return Ok(None),
+            };
+
+            let token = match ch {
+                c if c.is_whitespace() => continue,
+                '%' if column == 1 => {
+                    // Escaped line.
+                    self.skip_line();
+                    continue;
+                }
+                ';' => {
+                    self.skip_line();
+                    continue;
+                }
+                '{' => {
+                    loop {
+                        match self.next_char() {
+                            Some('}') => break,
+                            Some(_) => {}
+                            None => return UnterminatedComment { line, column }.fail(),
+                        }
+                    }
+                    continue;
+                }
+                '[' => self.read_tag(line, column)?,
+                '(' => Token::OpenVariation,
+                ')' => Token::CloseVariation,
+                '$' => {
+                    while self
+                        .chars
+                        .peek()
+                        .map(|c| c.is_ascii_digit())
+                        .unwrap_or(false)
+                    {
+                        self.next_char();
+                    }
+                    Token::Nag
+                }
+                c => {
+                    let mut symbol = c.to_string();
+                    while let Some(&next) = self.chars.peek() {
+                        if next.is_whitespace() || "[]{}();$".contains(next) {
+                            break;
+                        }
+                        symbol.push(next);
+                        self.next_char();
+                    }
+                    Token::Symbol(symbol)
+                }
+            };
+
+            return Ok(Some((token, line, column)));
+        }
+    }
+}
+
+/// A game being read.
+#[derive(Default)]
+struct GameReader {
+    tags: Vec<(String, String)>,
+    game: Option<Game>,
+    result: Option<GameResult>,
+}
+
+impl GameReader {
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.game.is_none() && self.result.is_none()
+    }
+
+    fn game(&mut self, line: usize, column: usize) -> Result<&mut Game> {
+        if self.game.is_none() {
+            let starting_position = match self.tags.iter().find(|(name, _)| name == "FEN") {
+                Some((_, fen)) => Board::from_fen(fen).context(InvalidFenTag { line, column })?,
+                None => board_with_setup(),
+            };
+            self.game = Some(Game::from_position(starting_position));
+        }
+
+        Ok(self.game.as_mut().unwrap())
+    }
+
+    fn finish(mut self, line: usize, column: usize) -> Result<PgnGame> {
+        self.game(line, column)?;
+
+        let tag_result = self
+            .tags
+            .iter()
+            .find(|(name, _)| name == "Result")
+            .and_then(|(_, value)| GameResult::from_pgn(value));
+        let result = self.result.or(tag_result).unwrap_or(GameResult::Unknown);
+
+        let tags = self
+            .tags
+            .into_iter()
+            .filter(|(name, _)| name != "Result")
+            .collect();
+
+        Ok(PgnGame {
+            tags,
+            game: self.game.unwrap(),
+            result,
+        })
+    }
+}
+
+/// Strips a move number (e.g. "12." or "12...") from the start of a symbol.
+fn strip_move_number(symbol: &str) -> &str {
+    let without_number = symbol.trim_start_matches(|c: char| c.is_ascii_digit());
+    if without_number.len() < symbol.len() && without_number.starts_with('.') {
+        without_number.trim_start_matches('.')
+    } else {
+        symbol
+    }
+}
+
+/// Reads every game from a PGN file.
+///
+/// Comments, NAGs and variations are skipped, only the main line of each game is kept.
+pub fn read_pgn(text: &str) -> Result<Vec<PgnGame>> {
+    let mut tokenizer = Tokenizer::new(text);
+    let mut games = Vec::new();
+    let mut current = GameReader::default();
+    let mut variation_depth = 0;
+
+    while let Some((token, line, column)) = tokenizer.next_token()? {
+        match token {
+            Token::Tag(name, value) => {
+                // A tag after some moves starts a new game.
+                if current.game.is_some() {
+                    let previous = std::mem::take(&mut current);
+                    games.push(previous.finish(line, column)?);
+                }
+                current.tags.push((name, value));
+            }
+            Token::OpenVariation => variation_depth += 1,
+            Token::CloseVariation => {
+                if variation_depth == 0 {
+                    return UnbalancedVariation { line, column }.fail();
+                }
+                variation_depth -= 1;
+            }
+            Token::Nag => {}
+            Token::Symbol(_) if variation_depth > 0 => {}
+            Token::Symbol(symbol) => {
+                if let Some(result) = GameResult::from_pgn(&symbol) {
+                    current.result = Some(result);
+                    let finished = std::mem::take(&mut current);
+                    games.push(finished.finish(line, column)?);
+                    continue;
+                }
+
+                let san = strip_move_number(&symbol);
+                if san.is_empty() {
+                    continue;
+                }
+                let game = current.game(line, column)?;
+                let mv = game.position().parse_san(san).context(IllegalMove {
+                    line,
+                    column,
+                    san: san.to_string(),
+                })?;
+
+                game.play(&mv).unwrap(); // Unwrap safe because `parse_san` only returns legal moves.
+            }
+        }
+    }
+
+    if variation_depth > 0 {
+        return UnbalancedVariation {
+            line: tokenizer.line,
+            column: tokenizer.column,
+        }
+        .fail();
+    }
+
+    if !current.is_empty() {
+        games.push(current.finish(tokenizer.line, tokenizer.column)?);
+    }
+
+    Ok(games)
+}
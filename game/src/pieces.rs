@@ -10,12 +10,19 @@ pub enum PieceError {
 type Result<T> = std::result::Result<T, PieceError>;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Color {
     Black,
     White,
 }
 
 impl Color {
+    /// Index of the color in per-color tables.
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+
     pub fn opposite(&self) -> Color {
         match self {
             Color::Black => Color::White,
@@ -45,6 +52,8 @@ impl TryFrom<String> for Color {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum PieceType {
     Pawn,
     Bishop,
@@ -54,6 +63,13 @@ pub enum PieceType {
     King,
 }
 
+impl PieceType {
+    /// Index of the piece type in per-type tables.
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+}
+
 impl From<PieceType> for String {
     fn from(s: PieceType) -> Self {
         match s {
@@ -84,20 +100,16 @@ impl TryFrom<String> for PieceType {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Piece {
     pub piece_type: PieceType,
     pub color: Color,
-    pub moved_once: bool,
 }
 
 impl Piece {
     pub fn new(piece_type: PieceType, color: Color) -> Self {
-        Self {
-            piece_type,
-            color,
-            moved_once: false,
-        }
+        Self { piece_type, color }
     }
 
     pub fn from_notation(notation: char) -> Result<Self> {
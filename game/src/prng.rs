@@ -0,0 +1,13 @@
+//! Pseudo-random numbers for the tables built when the crate starts, which must come out the
+//! same on every run.
+
+/// Step of a xorshift64* generator, returning the next state and the generated number.
+///
+/// A `const fn`, so that tables can be generated at compile time.
+pub(crate) const fn xorshift64_star(state: u64) -> (u64, u64) {
+    let mut s = state;
+    s ^= s >> 12;
+    s ^= s << 25;
+    s ^= s >> 27;
+    (s, s.wrapping_mul(2_685_821_657_736_338_717))
+}
@@ -0,0 +1,913 @@
+//! Search for the best move in a position.
+//!
+//! The search is a negamax with alpha-beta pruning, run with iterative deepening: the position
+//! is searched one ply deeper at a time until a limit is reached, keeping the result of the last
+//! iteration that completed. Each iteration tries the principal variation of the previous one
+//! first, so that the best moves found so far are the first to narrow the window.
+//!
+//! Once the depth is used up, only captures are searched further, until the position is quiet
+//! enough for its static evaluation to be trusted.
+//!
+//! Results are kept in a transposition table, so that positions reached again, through another
+//! move order or in a later iteration, are searched from their best move or not at all.
+//!
+//! The search is selective: moves after the first are only searched with a null window, to
+//! prove they are no better, and lines unlikely to matter are pruned or searched shallower, see
+//! `SearchOptions`.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::move_picker::{mvv_lva, MovePicker, OrderingStats, OrderingTables};
+use crate::transposition::{Bound, TableStats, TranspositionTable, DEFAULT_TABLE_SIZE_MB};
+use crate::{Board, Color, Game, Move, PieceType};
+
+/// The deepest iteration the search runs, in plies.
+pub const MAX_DEPTH: usize = 64;
+
+/// The furthest from the root a position is searched, in plies.
+const MAX_PLY: usize = 128;
+
+/// The score of checkmating the opponent. Each ply to the mate is taken off, so that shorter
+/// mates score higher.
+const MATE: i32 = 32_000;
+
+/// Scores at least this far from zero are mates.
+pub(crate) const MATE_BOUND: i32 = MATE - MAX_PLY as i32;
+
+/// Higher than any score.
+const INFINITY: i32 = MATE + 1;
+
+/// Number of positions searched between two looks at the clock and the stop flag.
+const CHECK_INTERVAL: u64 = 1024;
+
+/// Half-moves without a capture or pawn move after which a position is scored as a draw.
+const FIFTY_MOVE_RULE_HALF_MOVES: usize = 100;
+
+/// Half-width of the first window searched around the previous score, and the depth from which
+/// windows are used.
+const ASPIRATION_WINDOW: i32 = 25;
+const ASPIRATION_MIN_DEPTH: usize = 4;
+
+/// How much shallower the position is searched after passing, besides a ply more every 6 plies
+/// of depth, and the depth from which passing is tried.
+const NULL_MOVE_REDUCTION: usize = 2;
+const NULL_MOVE_MIN_DEPTH: usize = 3;
+
+/// How far the score can move per ply of depth left, for futility pruning, and the depth up to
+/// which positions are pruned.
+const FUTILITY_MARGIN: i32 = 150;
+const FUTILITY_MAX_DEPTH: usize = 3;
+
+/// The depth from which, and number of moves searched after which, moves are reduced.
+const LMR_MIN_DEPTH: usize = 3;
+const LMR_MIN_MOVES: usize = 3;
+
+/// When to stop searching.
+///
+/// The search stops at the first limit it reaches. Without any, it runs until it is stopped with
+/// `Search::stop` or reaches `MAX_DEPTH`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchLimits {
+    /// The deepest iteration to run, in plies.
+    pub depth: Option<usize>,
+
+    /// The number of positions to search.
+    pub nodes: Option<u64>,
+
+    /// How long to search for. While pondering, the time only starts counting once the ponder
+    /// flag is cleared, see `Search::ponder_flag`.
+    pub time: Option<Duration>,
+
+    /// The moves to choose from, all legal moves when empty or when none of them is legal.
+    pub root_moves: Vec<Move>,
+}
+
+impl SearchLimits {
+    /// Searches up to a depth, in plies.
+    pub fn depth(depth: usize) -> Self {
+        SearchLimits {
+            depth: Some(depth),
+            ..Default::default()
+        }
+    }
+
+    /// Searches up to a number of positions.
+    pub fn nodes(nodes: u64) -> Self {
+        SearchLimits {
+            nodes: Some(nodes),
+            ..Default::default()
+        }
+    }
+
+    /// Searches for a given time.
+    pub fn time(time: Duration) -> Self {
+        SearchLimits {
+            time: Some(time),
+            ..Default::default()
+        }
+    }
+}
+
+/// The selective search techniques used by the search.
+///
+/// Each skips, or searches shallower, lines that are unlikely to matter, so that the search gets
+/// deeper in the same time at the risk of missing some moves. They are all used by default, and
+/// can be turned off one at a time to measure what each brings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SearchOptions {
+    /// Skip positions whose score stays above beta even when passing the turn. Never used when
+    /// the side to play only has pawns left, as having to move is then often a disadvantage
+    /// (zugzwang).
+    pub null_move_pruning: bool,
+
+    /// Search the moves ordered last shallower, searching again at full depth those that turn
+    /// out better than expected.
+    pub late_move_reductions: bool,
+
+    /// Skip the quiet moves of positions near the horizon that are too far below alpha for a
+    /// quiet move to catch up.
+    pub futility_pruning: bool,
+
+    /// Stop at positions near the horizon that are too far above beta for the opponent to
+    /// catch up.
+    pub reverse_futility_pruning: bool,
+
+    /// Search a ply deeper after a move giving check.
+    pub check_extensions: bool,
+
+    /// Search the root within a narrow window around the score of the previous iteration, only
+    /// widening it when the score falls out of it.
+    pub aspiration_windows: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            null_move_pruning: true,
+            late_move_reductions: true,
+            futility_pruning: true,
+            reverse_futility_pruning: true,
+            check_extensions: true,
+            aspiration_windows: true,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// No selective search: a plain alpha-beta search of every move to the same depth.
+    pub fn none() -> Self {
+        SearchOptions {
+            null_move_pruning: false,
+            late_move_reductions: false,
+            futility_pruning: false,
+            reverse_futility_pruning: false,
+            check_extensions: false,
+            aspiration_windows: false,
+        }
+    }
+}
+
+/// The score of a position, from the point of view of the side to play.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Score {
+    /// An advantage in hundredths of a pawn.
+    Centipawns(i32),
+
+    /// A forced mate in a number of moves, positive when the side to play mates and negative
+    /// when it gets mated. Zero when the side to play is already checkmated.
+    Mate(i32),
+}
+
+impl Score {
+    fn from_value(value: i32) -> Self {
+        if value >= MATE_BOUND {
+            Score::Mate((MATE - value + 1) / 2)
+        } else if value <= -MATE_BOUND {
+            Score::Mate(-(MATE + value) / 2)
+        } else {
+            Score::Centipawns(value)
+        }
+    }
+
+    /// Whether the score is a forced mate, for either side.
+    pub fn is_mate(&self) -> bool {
+        matches!(self, Score::Mate(_))
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Score::Centipawns(cp) => write!(f, "{:+.2}", *cp as f64 / 100.0),
+            Score::Mate(moves) => write!(f, "#{}", moves),
+        }
+    }
+}
+
+/// The outcome of a search.
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    /// The best move found, `None` when the side to play has no legal move.
+    pub best_move: Option<Move>,
+
+    pub score: Score,
+
+    /// The line expected from both sides, starting with the best move.
+    pub pv: Vec<Move>,
+
+    /// The depth of the last iteration that completed, in plies.
+    pub depth: usize,
+
+    /// The number of positions searched, over all iterations.
+    pub nodes: u64,
+
+    /// How long the search took.
+    pub time: Duration,
+
+    /// How the transposition table was used during the search.
+    pub table_stats: TableStats,
+
+    /// How well moves were ordered during the search.
+    pub ordering_stats: OrderingStats,
+}
+
+/// A search for the best move, which can be stopped from another thread.
+pub struct Search {
+    stop: Arc<AtomicBool>,
+    ponder: Arc<AtomicBool>,
+    options: SearchOptions,
+    limits: SearchLimits,
+    start: Instant,
+    nodes: u64,
+    stopped: bool,
+
+    /// When the time limit started counting, and whether it hasn't yet because the search is
+    /// pondering.
+    clock_start: Instant,
+    pondering: bool,
+
+    /// The legal moves searched at the root.
+    root_moves: Vec<Move>,
+
+    /// Hashes of the positions from the start of the game to the one being searched.
+    path: Vec<u64>,
+
+    /// The principal variation of the last iteration that completed.
+    pv: Vec<Move>,
+
+    /// The moves leading from the root to the position being searched, `None` for a turn
+    /// passed by null-move pruning.
+    line: Vec<Option<Move>>,
+
+    /// Kept from one search to the next.
+    table: TranspositionTable,
+    ordering: OrderingTables,
+    ordering_stats: OrderingStats,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Search::with_table_size(DEFAULT_TABLE_SIZE_MB)
+    }
+
+    /// Creates a search whose transposition table takes up about `size_mb` megabytes.
+    pub fn with_table_size(size_mb: usize) -> Self {
+        Search {
+            stop: Arc::new(AtomicBool::new(false)),
+            ponder: Arc::new(AtomicBool::new(false)),
+            options: SearchOptions::default(),
+            limits: SearchLimits::default(),
+            start: Instant::now(),
+            nodes: 0,
+            stopped: false,
+            clock_start: Instant::now(),
+            pondering: false,
+            root_moves: Vec::new(),
+            path: Vec::new(),
+            pv: Vec::new(),
+            line: Vec::new(),
+            table: TranspositionTable::new(size_mb),
+            ordering: OrderingTables::new(MAX_PLY),
+            ordering_stats: OrderingStats::default(),
+        }
+    }
+
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    /// Sets the selective search techniques used by the next searches.
+    pub fn set_options(&mut self, options: SearchOptions) {
+        self.options = options;
+    }
+
+    /// Resizes the transposition table to about `size_mb` megabytes, clearing it.
+    pub fn set_table_size(&mut self, size_mb: usize) {
+        self.table = TranspositionTable::new(size_mb);
+    }
+
+    /// Clears the transposition table, for instance before searching a new game.
+    pub fn clear_table(&mut self) {
+        self.table.clear();
+    }
+
+    /// Returns the flag stopping the search.
+    ///
+    /// Setting it, from any thread, stops the search in progress, which then returns the result
+    /// of its last completed iteration. The flag is cleared when the search returns.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    /// Returns the flag telling that the search is pondering: thinking on the opponent's time,
+    /// about the move it is expected to play.
+    ///
+    /// When set before the search starts, the time limit doesn't count until the flag is
+    /// cleared, from any thread, once the opponent has played the expected move. The flag is
+    /// cleared when the search returns.
+    pub fn ponder_flag(&self) -> Arc<AtomicBool> {
+        self.ponder.clone()
+    }
+
+    /// Stops the search in progress, see `stop_flag`.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Searches a position for its best move.
+    pub fn run(&mut self, board: &Board, limits: &SearchLimits) -> SearchResult {
+        self.search(board, vec![board.hash()], limits, &mut |_| {})
+    }
+
+    /// Searches the current position of a game for its best move, scoring lines that repeat a
+    /// position of the game as draws.
+    pub fn run_game(&mut self, game: &Game, limits: &SearchLimits) -> SearchResult {
+        self.run_game_with_progress(game, limits, |_| {})
+    }
+
+    /// Searches the current position of a game like `run_game`, calling `progress` with the
+    /// result so far each time an iteration completes.
+    pub fn run_game_with_progress<F>(
+        &mut self,
+        game: &Game,
+        limits: &SearchLimits,
+        mut progress: F,
+    ) -> SearchResult
+    where
+        F: FnMut(&SearchResult),
+    {
+        let path = game.positions().map(Board::hash).collect();
+        self.search(game.position(), path, limits, &mut progress)
+    }
+
+    fn search(
+        &mut self,
+        board: &Board,
+        path: Vec<u64>,
+        limits: &SearchLimits,
+        progress: &mut dyn FnMut(&SearchResult),
+    ) -> SearchResult {
+        self.limits = limits.clone();
+        self.start = Instant::now();
+        self.clock_start = self.start;
+        self.pondering = self.ponder.load(Ordering::Relaxed);
+        self.nodes = 0;
+        self.stopped = false;
+        self.path = path;
+        self.pv.clear();
+        self.line.clear();
+        self.table.new_search();
+        self.ordering.new_search();
+        self.ordering_stats = OrderingStats::default();
+
+        let mut board = board.clone();
+        let mut moves = board.legal_moves();
+        if moves.iter().any(|mv| limits.root_moves.contains(mv)) {
+            moves.retain(|mv| limits.root_moves.contains(mv));
+        }
+        self.root_moves = moves.clone();
+        let mut result = SearchResult {
+            best_move: None,
+            score: Score::Centipawns(board.evaluate()),
+            pv: Vec::new(),
+            depth: 0,
+            nodes: 0,
+            time: Duration::default(),
+            table_stats: TableStats::default(),
+            ordering_stats: OrderingStats::default(),
+        };
+
+        if moves.is_empty() {
+            result.score = if board.is_check() {
+                Score::Mate(0)
+            } else {
+                Score::Centipawns(0)
+            };
+        } else {
+            // Play something even if the first iteration doesn't complete.
+            result.best_move = moves.into_iter().next();
+
+            let max_depth = limits.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH);
+            let mut value = 0;
+            for depth in 1..=max_depth {
+                let mut pv = Vec::new();
+                value = self.search_root(&mut board, depth, value, &mut pv);
+                if self.stopped {
+                    break;
+                }
+
+                self.pv = pv.clone();
+                result.best_move = pv.first().cloned();
+                result.score = Score::from_value(value);
+                result.pv = pv;
+                result.depth = depth;
+                self.fill_stats(&mut result);
+                progress(&result);
+
+                // A deeper search can't find a shorter mate, and is unlikely to complete in the
+                // time left once half of it is gone.
+                let mate_found = value.abs() >= MATE_BOUND && MATE - value.abs() <= depth as i32;
+                let out_of_time = match (limits.time, self.clock()) {
+                    (Some(time), Some(elapsed)) => elapsed >= time / 2,
+                    _ => false,
+                };
+                if mate_found || out_of_time {
+                    break;
+                }
+            }
+        }
+
+        self.stop.store(false, Ordering::Relaxed);
+        self.ponder.store(false, Ordering::Relaxed);
+        self.fill_stats(&mut result);
+        result
+    }
+
+    /// Sets the statistics of a result to those of the search so far.
+    fn fill_stats(&self, result: &mut SearchResult) {
+        result.nodes = self.nodes;
+        result.time = self.start.elapsed();
+        result.table_stats = self.table.stats();
+        result.ordering_stats = self.ordering_stats;
+    }
+
+    /// Searches the root position to a depth, within an aspiration window around the score of
+    /// the previous iteration when enabled.
+    ///
+    /// A narrow window prunes more, as long as the score stays inside it. When it falls out,
+    /// the window is widened on that side and the position searched again.
+    fn search_root(
+        &mut self,
+        board: &mut Board,
+        depth: usize,
+        previous: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        if !self.options.aspiration_windows
+            || depth < ASPIRATION_MIN_DEPTH
+            || previous.abs() >= MATE_BOUND
+        {
+            return self.negamax(board, depth, 0, -INFINITY, INFINITY, true, pv);
+        }
+
+        let mut delta = ASPIRATION_WINDOW;
+        let mut alpha = previous - delta;
+        let mut beta = previous + delta;
+        loop {
+            let value = self.negamax(board, depth, 0, alpha, beta, true, pv);
+            if self.stopped {
+                return value;
+            }
+
+            delta *= 2;
+            if value <= alpha {
+                alpha = (value - delta).max(-INFINITY);
+            } else if value >= beta {
+                beta = (value + delta).min(INFINITY);
+            } else {
+                return value;
+            }
+        }
+    }
+
+    /// Returns the score of a position for the side to play, filling in the principal variation
+    /// when it lies within the window.
+    ///
+    /// `on_pv` tells whether the moves leading to the position follow the principal variation of
+    /// the previous iteration, whose next move is then tried first. Otherwise the best move
+    /// stored in the transposition table is.
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        on_pv: bool,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        pv.clear();
+        if depth == 0 {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+
+        if !self.visit_node() {
+            return 0;
+        }
+
+        if ply > 0 && self.is_draw(board) {
+            return 0;
+        }
+
+        let hash = board.hash();
+        let mut hash_move = None;
+        if let Some(entry) = self.table.probe(hash, ply) {
+            // A score within the window would need the line leading to it, which the table
+            // doesn't keep.
+            let cutoff = match entry.bound {
+                Bound::Exact => entry.score <= alpha || entry.score >= beta,
+                Bound::Lower => entry.score >= beta,
+                Bound::Upper => entry.score <= alpha,
+            };
+            if ply > 0 && entry.depth as usize >= depth && cutoff {
+                self.table.record_cutoff();
+                return entry.score;
+            }
+            hash_move = entry.best_move;
+        }
+
+        let moves = if ply == 0 {
+            self.root_moves.clone()
+        } else {
+            board.legal_moves()
+        };
+        let in_check = board.is_check();
+        if moves.is_empty() {
+            return if in_check { -MATE + ply as i32 } else { 0 };
+        }
+
+        if ply >= MAX_PLY {
+            return board.evaluate();
+        }
+
+        // Pruning relies on the static evaluation, which can't be trusted in check, and is not
+        // done when looking for the exact score of the principal variation.
+        let pv_node = beta - alpha > 1;
+        let mut futile = false;
+        if !pv_node && !in_check {
+            let static_eval = board.evaluate();
+            if self.options.reverse_futility_pruning
+                && depth <= FUTILITY_MAX_DEPTH
+                && beta.abs() < MATE_BOUND
+                && static_eval - FUTILITY_MARGIN * depth as i32 >= beta
+            {
+                return static_eval;
+            }
+
+            let passed_last_turn = matches!(self.line.last(), Some(None));
+            if self.options.null_move_pruning
+                && depth >= NULL_MOVE_MIN_DEPTH
+                && static_eval >= beta
+                && !passed_last_turn
+                && has_pieces(board, board.to_play)
+            {
+                let reduction = NULL_MOVE_REDUCTION + depth / 6;
+                let undo = board.make_null_move();
+                self.path.push(board.hash());
+                self.line.push(None);
+                let mut child_pv = Vec::new();
+                let score = -self.negamax(
+                    board,
+                    (depth - 1).saturating_sub(reduction),
+                    ply + 1,
+                    -beta,
+                    -beta + 1,
+                    false,
+                    &mut child_pv,
+                );
+                self.line.pop();
+                self.path.pop();
+                board.unmake_null_move(undo);
+
+                if self.stopped {
+                    return 0;
+                }
+
+                // A mate found after passing proves nothing, as passing isn't legal.
+                if score >= beta {
+                    return if score >= MATE_BOUND { beta } else { score };
+                }
+            }
+
+            futile = self.options.futility_pruning
+                && depth <= FUTILITY_MAX_DEPTH
+                && alpha.abs() < MATE_BOUND
+                && static_eval + FUTILITY_MARGIN * (depth as i32) <= alpha;
+        }
+
+        let pv_move = if on_pv {
+            self.pv.get(ply).cloned()
+        } else {
+            None
+        };
+        let previous = self.line.last().cloned().flatten();
+        let mut picker = MovePicker::new(
+            board,
+            moves,
+            pv_move.as_ref().or(hash_move.as_ref()),
+            &self.ordering,
+            ply,
+            previous.as_ref(),
+        );
+
+        let color = board.to_play;
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        let mut best_move = None;
+        let mut moves_searched = 0;
+        let mut quiets_tried = Vec::new();
+        let mut child_pv = Vec::new();
+        while let Some(mv) = picker.next_move(board) {
+            let is_quiet = !mv.is_capture() && !mv.is_promotion();
+            let undo = board.make_move(mv.clone());
+            let gives_check = board.is_check();
+            if futile && moves_searched > 0 && is_quiet && !gives_check {
+                board.unmake_move(mv, undo);
+                continue;
+            }
+
+            self.path.push(board.hash());
+            self.line.push(Some(mv.clone()));
+            let extension = if self.options.check_extensions && gives_check {
+                1
+            } else {
+                0
+            };
+            let new_depth = depth - 1 + extension;
+            let child_on_pv = pv_move.as_ref() == Some(&mv);
+            let mut score;
+            if moves_searched == 0 {
+                score = -self.negamax(
+                    board,
+                    new_depth,
+                    ply + 1,
+                    -beta,
+                    -alpha,
+                    child_on_pv,
+                    &mut child_pv,
+                );
+            } else {
+                // Only prove that the move is no better than the best one so far, searching
+                // again with the full window and depth if it is.
+                let reduction = if self.options.late_move_reductions
+                    && depth >= LMR_MIN_DEPTH
+                    && moves_searched >= LMR_MIN_MOVES
+                    && is_quiet
+                    && !in_check
+                    && !gives_check
+                {
+                    late_move_reduction(depth, moves_searched)
+                } else {
+                    0
+                };
+                score = -self.negamax(
+                    board,
+                    new_depth.saturating_sub(reduction),
+                    ply + 1,
+                    -alpha - 1,
+                    -alpha,
+                    child_on_pv,
+                    &mut child_pv,
+                );
+                if score > alpha && reduction > 0 {
+                    score = -self.negamax(
+                        board,
+                        new_depth,
+                        ply + 1,
+                        -alpha - 1,
+                        -alpha,
+                        child_on_pv,
+                        &mut child_pv,
+                    );
+                }
+                if score > alpha && score < beta {
+                    score = -self.negamax(
+                        board,
+                        new_depth,
+                        ply + 1,
+                        -beta,
+                        -alpha,
+                        child_on_pv,
+                        &mut child_pv,
+                    );
+                }
+            }
+            self.line.pop();
+            self.path.pop();
+            board.unmake_move(mv.clone(), undo);
+
+            if self.stopped {
+                return 0;
+            }
+            moves_searched += 1;
+
+            if score > best {
+                best = score;
+                if score > alpha {
+                    alpha = score;
+                    best_move = Some(mv.clone());
+                    if alpha >= beta {
+                        self.ordering_stats.cutoffs += 1;
+                        if moves_searched == 1 {
+                            self.ordering_stats.first_move_cutoffs += 1;
+                        }
+                        if is_quiet {
+                            self.ordering.record_refutation(
+                                color,
+                                ply,
+                                depth,
+                                &mv,
+                                previous.as_ref(),
+                                &quiets_tried,
+                            );
+                        }
+                        break;
+                    }
+
+                    pv.clear();
+                    pv.push(mv);
+                    pv.append(&mut child_pv);
+                    continue;
+                }
+            }
+
+            if is_quiet {
+                quiets_tried.push(mv);
+            }
+        }
+
+        let bound = if best >= beta {
+            Bound::Lower
+        } else if best > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        self.table.store(hash, ply, depth, bound, best, best_move);
+
+        best
+    }
+
+    /// Returns the score of a position once the exchanges going on have played out, searching
+    /// only captures and queen promotions, or every move when in check.
+    ///
+    /// Out of check, the side to play doesn't have to capture and can stand pat on the static
+    /// evaluation. Captures losing material according to `Board::see` are not searched.
+    fn quiescence(&mut self, board: &mut Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        if !self.visit_node() {
+            return 0;
+        }
+
+        if ply > 0 && self.is_draw(board) {
+            return 0;
+        }
+
+        let in_check = board.is_check();
+        let mut moves = board.legal_moves();
+        if moves.is_empty() {
+            return if in_check { -MATE + ply as i32 } else { 0 };
+        }
+
+        if ply >= MAX_PLY {
+            return board.evaluate();
+        }
+
+        let mut best = -INFINITY;
+        if !in_check {
+            best = board.evaluate();
+            if best >= beta {
+                return best;
+            }
+            alpha = alpha.max(best);
+
+            moves.retain(|mv| {
+                (mv.is_capture() || mv.promotion == Some(PieceType::Queen)) && board.see(mv) >= 0
+            });
+
+            // Take the most valuable victims first, with the least valuable attackers.
+            moves.sort_by_key(|mv| -mvv_lva(mv));
+        }
+
+        for mv in moves {
+            let undo = board.make_move(mv.clone());
+            self.path.push(board.hash());
+            self.line.push(Some(mv.clone()));
+            let score = -self.quiescence(board, ply + 1, -beta, -alpha);
+            self.line.pop();
+            self.path.pop();
+            board.unmake_move(mv, undo);
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score > best {
+                best = score;
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Counts a position as searched, unless the search has to stop first.
+    fn visit_node(&mut self) -> bool {
+        if self.should_stop() {
+            self.stopped = true;
+            return false;
+        }
+        self.nodes += 1;
+        true
+    }
+
+    /// Whether a position reached during the search is drawn, by the fifty-move rule, lack of
+    /// material, or by repeating an earlier position.
+    fn is_draw(&self, board: &Board) -> bool {
+        if board.half_move_clock >= FIFTY_MOVE_RULE_HALF_MOVES || board.has_insufficient_material()
+        {
+            return true;
+        }
+
+        // Only the positions since the last capture or pawn move, with the same side to play,
+        // can be the same.
+        let hash = board.hash();
+        self.path
+            .iter()
+            .rev()
+            .take(board.half_move_clock + 1)
+            .skip(2)
+            .step_by(2)
+            .any(|h| *h == hash)
+    }
+
+    /// How long the time limit has been counting, `None` while pondering.
+    fn clock(&mut self) -> Option<Duration> {
+        if self.pondering {
+            if self.ponder.load(Ordering::Relaxed) {
+                return None;
+            }
+            self.pondering = false;
+            self.clock_start = Instant::now();
+        }
+        Some(self.clock_start.elapsed())
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if let Some(nodes) = self.limits.nodes {
+            if self.nodes >= nodes {
+                return true;
+            }
+        }
+
+        if !self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            return false;
+        }
+
+        if self.stop.load(Ordering::Relaxed) {
+            return true;
+        }
+        match (self.limits.time, self.clock()) {
+            (Some(time), Some(elapsed)) => elapsed >= time,
+            _ => false,
+        }
+    }
+}
+
+/// Whether a side has pieces left besides its pawns and king.
+fn has_pieces(board: &Board, color: Color) -> bool {
+    let pawns_and_kings =
+        board.pieces[PieceType::Pawn.index()] | board.pieces[PieceType::King.index()];
+    !(board.colors[color.index()] & !pawns_and_kings).is_empty()
+}
+
+/// How many plies shallower a late move is searched, growing slowly with the depth and the
+/// number of moves searched before it.
+fn late_move_reduction(depth: usize, moves_searched: usize) -> usize {
+    let reduction = (depth as f64).ln() * (moves_searched as f64).ln() / 2.0;
+    (reduction as usize).max(1)
+}
@@ -1,3 +1,6 @@
+use crate::board::CastleState;
+use std::convert::TryFrom;
+
 use crate::constants::*;
 use crate::{Board, Color, Column, Piece, PieceType, Square};
 
@@ -12,12 +15,83 @@ const PIECES_ROW: &[PieceType] = &[
     PieceType::Rook,
 ];
 
+/// Number of Chess960 starting positions.
+const CHESS960_POSITIONS: usize = 960;
+
+/// Squares of the two knights among the five left once the bishops and queen are placed, for
+/// each knight code of the Chess960 numbering.
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
 pub fn board_with_setup() -> Board {
     let mut board = Board::default();
     setup_board(&mut board);
     board
 }
 
+/// Returns the Chess960 starting position with a given number, from 0 to 959.
+///
+/// Positions follow the standard numbering, position 518 being the classical setup. Returns
+/// `None` for numbers out of range.
+pub fn board_with_chess960_setup(n: usize) -> Option<Board> {
+    if n >= CHESS960_POSITIONS {
+        return None;
+    }
+
+    let mut pieces_row: [Option<PieceType>; BOARD_DIMENSION] = [None; BOARD_DIMENSION];
+
+    // The bishops go on opposite colors: the first on the files B, D, F and H, the second on
+    // the files A, C, E and G.
+    let n_rest = n / 4;
+    pieces_row[2 * (n % 4) + 1] = Some(PieceType::Bishop);
+    pieces_row[2 * (n_rest % 4)] = Some(PieceType::Bishop);
+    let n_rest = n_rest / 4;
+
+    // The queen and knights take the remaining squares in order.
+    let place_nth_empty = |row: &mut [Option<PieceType>], nth: usize, piece_type: PieceType| {
+        let col = (0..BOARD_DIMENSION)
+            .filter(|col| row[*col].is_none())
+            .nth(nth)
+            .unwrap();
+        row[col] = Some(piece_type);
+    };
+    place_nth_empty(&mut pieces_row, n_rest % 6, PieceType::Queen);
+    let (first_knight, second_knight) = KNIGHT_PLACEMENTS[n_rest / 6];
+    place_nth_empty(&mut pieces_row, second_knight, PieceType::Knight);
+    place_nth_empty(&mut pieces_row, first_knight, PieceType::Knight);
+
+    // The king goes between the rooks on the last three squares.
+    for piece_type in &[PieceType::Rook, PieceType::King, PieceType::Rook] {
+        place_nth_empty(&mut pieces_row, 0, *piece_type);
+    }
+
+    let pieces_row: Vec<PieceType> = pieces_row.iter().map(|p| p.unwrap()).collect();
+    let rook_cols: Vec<Column> = (0..BOARD_DIMENSION)
+        .filter(|col| pieces_row[*col] == PieceType::Rook)
+        .map(|col| Column::try_from(col).unwrap())
+        .collect();
+
+    let mut board = Board::default();
+    board.clear();
+    for color in &[Color::White, Color::Black] {
+        setup_pawn_row(&mut board, *color);
+        setup_pieces_row(&mut board, *color, &pieces_row);
+        board.castling_rook_cols[color.index()] = [rook_cols[1], rook_cols[0]];
+        board.set_castle_state(*color, CastleState::Both);
+    }
+    Some(board)
+}
+
 fn setup_pawn_row(board: &mut Board, color: Color) {
     let row = match color {
         Color::White => WHITE_PAWN_ROW,
@@ -36,14 +110,14 @@ fn setup_pawn_row(board: &mut Board, color: Color) {
     }
 }
 
-fn setup_pieces_row(board: &mut Board, color: Color) {
+fn setup_pieces_row(board: &mut Board, color: Color, pieces_row: &[PieceType]) {
     let row = match color {
         Color::White => WHITE_PIECES_ROW,
         Color::Black => BLACK_PIECES_ROW,
     };
 
     let mut square = Square::new(Column::A, row);
-    let mut pieces_iter = PIECES_ROW.iter();
+    let mut pieces_iter = pieces_row.iter();
 
     loop {
         let piece_type = pieces_iter.next().unwrap();
@@ -61,6 +135,8 @@ pub fn setup_board(board: &mut Board) {
     board.clear();
     for color in &[Color::White, Color::Black] {
         setup_pawn_row(board, *color);
-        setup_pieces_row(board, *color);
+        setup_pieces_row(board, *color, PIECES_ROW);
     }
+    board.set_castle_state(Color::White, CastleState::Both);
+    board.set_castle_state(Color::Black, CastleState::Both);
 }
@@ -1,17 +1,22 @@
 use std::convert::TryFrom;
 use std::fmt;
 use std::num::ParseIntError;
+use std::str::FromStr;
 
 use snafu::{ensure, ResultExt, Snafu};
 
 use crate::constants::BOARD_DIMENSION;
 
 #[derive(Debug, Snafu, PartialEq)]
+#[snafu(visibility(pub(crate)))]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     ParseError,
     InvalidColumn,
     InvalidRow { source: ParseIntError },
     RowTooBig,
+    InvalidMoveLength,
+    InvalidPromotionPiece,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
@@ -112,28 +117,95 @@ impl From<Column> for usize {
 
 pub type Row = usize;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Square {
-    pub col: Column,
-    pub row: Row,
-}
+/// Number of squares on the board.
+const SQUARE_COUNT: usize = BOARD_DIMENSION * BOARD_DIMENSION;
+
+/// A square of the board, stored as its index from A1 to H1 then up the rows.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square(u8);
 
 impl fmt::Display for Square {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}", String::from(self.col), self.row + 1)
+        write!(f, "{}{}", String::from(self.col()), self.row() + 1)
+    }
+}
+
+impl fmt::Debug for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
     }
 }
 
 impl From<Square> for String {
     fn from(s: Square) -> Self {
-        format!("{}{}", String::from(s.col), s.row)
+        s.to_string()
+    }
+}
+
+impl FromStr for Square {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Square::from_notation(s)
+    }
+}
+
+impl TryFrom<usize> for Square {
+    type Error = Error;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        ensure!(index < SQUARE_COUNT, ParseError);
+        Ok(Square(index as u8))
     }
 }
 
 impl Square {
+    /// Every square, from A1 to H1 then up the rows.
+    pub const ALL: [Square; SQUARE_COUNT] = {
+        let mut all = [Square(0); SQUARE_COUNT];
+        let mut index = 0;
+        while index < SQUARE_COUNT {
+            all[index] = Square(index as u8);
+            index += 1;
+        }
+        all
+    };
+
+    /// Returns the square on a given column and row.
+    ///
+    /// Panics if the row is off the board, see `try_new`.
     pub fn new(col: Column, row: Row) -> Square {
-        assert!(row < BOARD_DIMENSION);
-        Square { col, row }
+        Square::try_new(col, row).expect("row off the board")
+    }
+
+    /// Returns the square on a given column and row, if the row is on the board.
+    pub fn try_new(col: Column, row: Row) -> Option<Square> {
+        if row < BOARD_DIMENSION {
+            Some(Square((row * BOARD_DIMENSION + usize::from(col)) as u8))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the square with a given index, counting from A1 to H1 then up the rows.
+    ///
+    /// Panics if the index is 64 or more, see `Square::try_from`.
+    pub fn from_index(index: usize) -> Square {
+        assert!(index < SQUARE_COUNT);
+        Square(index as u8)
+    }
+
+    /// Index of the square, counting from A1 to H1 then up the rows.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn col(self) -> Column {
+        Column::try_from(self.index() % BOARD_DIMENSION).unwrap()
+    }
+
+    pub fn row(self) -> Row {
+        self.index() / BOARD_DIMENSION
     }
 
     pub fn from_notation<T: AsRef<str>>(notation: T) -> Result<Square, Error> {
@@ -151,75 +223,89 @@ impl Square {
         Ok(Square::new(col, row - 1))
     }
 
-    pub fn col_move(mut self, delta: i32) -> Option<Square> {
-        for _ in 0..delta.abs() {
-            if delta > 0 {
-                self = self.next_col()?;
-            } else {
-                self = self.prev_col()?;
-            }
+    /// Returns the square a number of columns and rows away, if it is on the board.
+    pub fn relative(self, col_delta: i32, row_delta: i32) -> Option<Square> {
+        let col = usize::from(self.col()) as i32 + col_delta;
+        let row = self.row() as i32 + row_delta;
+        let on_board = 0..BOARD_DIMENSION as i32;
+        if on_board.contains(&col) && on_board.contains(&row) {
+            Some(Square::from_index(
+                row as usize * BOARD_DIMENSION + col as usize,
+            ))
+        } else {
+            None
         }
-        Some(self)
     }
 
-    pub fn next_col(mut self) -> Option<Square> {
-        let idx: usize = self.col.into();
-        match Column::try_from(idx + 1) {
-            Ok(col) => {
-                self.col = col;
-                Some(self)
-            }
-            Err(_) => None,
-        }
+    pub fn col_move(self, delta: i32) -> Option<Square> {
+        self.relative(delta, 0)
     }
 
-    pub fn prev_col(mut self) -> Option<Square> {
-        let idx: usize = self.col.into();
-        if idx == 0 {
-            return None;
-        }
+    pub fn next_col(self) -> Option<Square> {
+        self.relative(1, 0)
+    }
 
-        match Column::try_from(idx - 1) {
-            Ok(col) => {
-                self.col = col;
-                Some(self)
-            }
-            Err(_) => None,
-        }
+    pub fn prev_col(self) -> Option<Square> {
+        self.relative(-1, 0)
     }
 
-    pub fn row_move(mut self, delta: i32) -> Option<Square> {
-        for _ in 0..delta.abs() {
-            if delta > 0 {
-                self = self.next_row()?;
-            } else {
-                self = self.prev_row()?;
-            }
-        }
-        Some(self)
+    pub fn row_move(self, delta: i32) -> Option<Square> {
+        self.relative(0, delta)
     }
 
-    pub fn next_row(mut self) -> Option<Square> {
-        if self.row < (BOARD_DIMENSION - 1) {
-            self.row += 1;
-            Some(self)
-        } else {
-            None
-        }
+    pub fn next_row(self) -> Option<Square> {
+        self.relative(0, 1)
     }
 
-    pub fn prev_row(mut self) -> Option<Square> {
-        if self.row > 0 {
-            self.row -= 1;
-            Some(self)
-        } else {
-            None
-        }
+    pub fn prev_row(self) -> Option<Square> {
+        self.relative(0, -1)
+    }
+
+    /// The squares of the column of this one, from the first row up.
+    pub fn file(self) -> impl Iterator<Item = Square> {
+        let col = usize::from(self.col());
+        (0..BOARD_DIMENSION).map(move |row| Square::from_index(row * BOARD_DIMENSION + col))
+    }
+
+    /// The squares of the row of this one, from the A column on.
+    pub fn rank(self) -> impl Iterator<Item = Square> {
+        let start = self.row() * BOARD_DIMENSION;
+        (start..start + BOARD_DIMENSION).map(Square::from_index)
+    }
+
+    /// The squares of the diagonal going up and right through this one, from the bottom up.
+    pub fn diagonal(self) -> impl Iterator<Item = Square> {
+        let steps = self.row().min(usize::from(self.col())) as i32;
+        let start = self.relative(-steps, -steps).unwrap();
+        (0..BOARD_DIMENSION as i32).map_while(move |step| start.relative(step, step))
+    }
+
+    /// The squares of the diagonal going up and left through this one, from the bottom up.
+    pub fn anti_diagonal(self) -> impl Iterator<Item = Square> {
+        let steps = self
+            .row()
+            .min(BOARD_DIMENSION - 1 - usize::from(self.col())) as i32;
+        let start = self.relative(steps, -steps).unwrap();
+        (0..BOARD_DIMENSION as i32).map_while(move |step| start.relative(-step, step))
+    }
+
+    /// Number of columns between two squares.
+    pub fn file_distance(self, other: Square) -> usize {
+        (usize::from(self.col()) as i32 - usize::from(other.col()) as i32).unsigned_abs() as usize
+    }
+
+    /// Number of rows between two squares.
+    pub fn rank_distance(self, other: Square) -> usize {
+        (self.row() as i32 - other.row() as i32).unsigned_abs() as usize
+    }
+
+    /// Number of king moves between two squares.
+    pub fn distance(self, other: Square) -> usize {
+        self.file_distance(other).max(self.rank_distance(other))
     }
 
-    pub fn relative(mut self, col_delta: i32, row_delta: i32) -> Option<Square> {
-        self = self.row_move(row_delta)?;
-        self = self.col_move(col_delta)?;
-        Some(self)
+    /// Number of rook moves between two squares on an empty board, going one square at a time.
+    pub fn manhattan_distance(self, other: Square) -> usize {
+        self.file_distance(other) + self.rank_distance(other)
     }
 }
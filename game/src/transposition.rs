@@ -0,0 +1,212 @@
+//! Transposition table, remembering what the search found about the positions it went through.
+//!
+//! The same position is often reached through different move orders. The table stores, for each
+//! position searched, how deep it was searched, its score and the best move found, so that the
+//! search can reuse them instead of searching the position again.
+
+use std::mem;
+
+use crate::search::MATE_BOUND;
+use crate::Move;
+
+/// The size of the table when not told otherwise, in megabytes.
+pub const DEFAULT_TABLE_SIZE_MB: usize = 16;
+
+/// Number of entries looked at to estimate how full the table is.
+const HASHFULL_SAMPLE: usize = 1000;
+
+/// How the stored score relates to the actual score of the position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Bound {
+    /// The score is exact.
+    Exact,
+
+    /// The position is worth at least the score, the search having stopped at a refutation.
+    Lower,
+
+    /// The position is worth at most the score, no move having reached it.
+    Upper,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Entry {
+    /// The hash of the position, telling apart the positions sharing a slot.
+    key: u64,
+
+    /// The search generation the entry was stored in.
+    generation: u8,
+
+    pub depth: u8,
+    pub bound: Bound,
+
+    /// The score of the position, with mates counted from the position rather than the root.
+    pub score: i32,
+
+    pub best_move: Option<Move>,
+}
+
+/// Statistics about the use of the transposition table during a search.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TableStats {
+    /// The number of positions looked up.
+    pub probes: u64,
+
+    /// The number of positions found in the table.
+    pub hits: u64,
+
+    /// The number of positions whose search was skipped thanks to the table.
+    pub cutoffs: u64,
+
+    /// The number of positions stored.
+    pub stores: u64,
+
+    /// How full the table is, in thousandths.
+    pub hashfull: usize,
+}
+
+impl TableStats {
+    /// The share of looked up positions found in the table, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        if self.probes == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.probes as f64
+        }
+    }
+}
+
+/// Converts a score relative to the root into one relative to the position `ply` plies away,
+/// for storing.
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    if score >= MATE_BOUND {
+        score + ply as i32
+    } else if score <= -MATE_BOUND {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+/// Converts a stored score back into one relative to the root.
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    if score >= MATE_BOUND {
+        score - ply as i32
+    } else if score <= -MATE_BOUND {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+/// A fixed-size table of search results, indexed by position hash.
+///
+/// Each position has a single slot, shared with the others hashing to it. A new entry replaces
+/// the one in its slot unless that one is for another position, was stored during the current
+/// search, and was searched deeper.
+pub(crate) struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    generation: u8,
+    stats: TableStats,
+}
+
+impl TranspositionTable {
+    /// Creates a table taking up about `size_mb` megabytes.
+    pub fn new(size_mb: usize) -> Self {
+        let count = (size_mb * 1024 * 1024 / mem::size_of::<Option<Entry>>()).max(1);
+        TranspositionTable {
+            entries: vec![None; count],
+            generation: 0,
+            stats: TableStats::default(),
+        }
+    }
+
+    /// Forgets every entry.
+    pub fn clear(&mut self) {
+        for entry in self.entries.iter_mut() {
+            *entry = None;
+        }
+        self.generation = 0;
+    }
+
+    /// Starts a new search, making the entries of previous ones the first to be replaced.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.stats = TableStats::default();
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        // Maps the hash onto the table without a division.
+        ((key as u128 * self.entries.len() as u128) >> 64) as usize
+    }
+
+    /// Looks up a position `ply` plies away from the root.
+    pub fn probe(&mut self, key: u64, ply: usize) -> Option<Entry> {
+        self.stats.probes += 1;
+        let entry = self.entries[self.slot(key)]
+            .as_ref()
+            .filter(|entry| entry.key == key)?;
+        self.stats.hits += 1;
+
+        let mut entry = entry.clone();
+        entry.score = score_from_table(entry.score, ply);
+        Some(entry)
+    }
+
+    /// Records that using an entry saved searching its position.
+    pub fn record_cutoff(&mut self) {
+        self.stats.cutoffs += 1;
+    }
+
+    /// Stores the result of searching a position `ply` plies away from the root.
+    pub fn store(
+        &mut self,
+        key: u64,
+        ply: usize,
+        depth: usize,
+        bound: Bound,
+        score: i32,
+        best_move: Option<Move>,
+    ) {
+        let generation = self.generation;
+        let slot = self.slot(key);
+        let existing = &mut self.entries[slot];
+        let replace = match existing {
+            None => true,
+            Some(entry) => {
+                entry.key == key || entry.generation != generation || depth >= entry.depth as usize
+            }
+        };
+        if !replace {
+            return;
+        }
+
+        // Keep the move already known for the position when no better one was found.
+        let best_move = match existing {
+            Some(entry) if best_move.is_none() && entry.key == key => entry.best_move.take(),
+            _ => best_move,
+        };
+        *existing = Some(Entry {
+            key,
+            generation,
+            depth: depth.min(u8::MAX as usize) as u8,
+            bound,
+            score: score_to_table(score, ply),
+            best_move,
+        });
+        self.stats.stores += 1;
+    }
+
+    /// Returns the statistics of the current search.
+    pub fn stats(&self) -> TableStats {
+        let sample = self.entries.len().min(HASHFULL_SAMPLE);
+        let used = self.entries[..sample]
+            .iter()
+            .flatten()
+            .filter(|entry| entry.generation == self.generation)
+            .count();
+        TableStats {
+            hashfull: used * 1000 / sample,
+            ..self.stats
+        }
+    }
+}
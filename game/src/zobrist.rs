@@ -0,0 +1,136 @@
+//! Zobrist keys used to hash positions.
+//!
+//! Each feature of a position (a piece on a square, the side to play, a castling right, the
+//! en-passant file when a capture is possible) gets a random key, and the hash of a position is
+//! the XOR of the keys of its features. Playing a move only needs to toggle the keys of what
+//! changed.
+
+use crate::attacks;
+use crate::board::CastleState;
+use crate::constants::{BOARD_DIMENSION, WHITE_PAWN_ROW};
+use crate::prng::xorshift64_star;
+use crate::{Board, Color, Piece, PieceType, Square};
+
+const SQUARE_COUNT: usize = BOARD_DIMENSION * BOARD_DIMENSION;
+
+struct ZobristKeys {
+    /// Indexed by color, piece type then square.
+    pieces: [[[u64; SQUARE_COUNT]; 6]; 2],
+    black_to_play: u64,
+    /// Indexed by color, then kingside and queenside.
+    castling: [[u64; 2]; 2],
+    /// Indexed by file.
+    en_passant: [u64; BOARD_DIMENSION],
+}
+
+impl ZobristKeys {
+    const fn new() -> Self {
+        let mut keys = ZobristKeys {
+            pieces: [[[0; SQUARE_COUNT]; 6]; 2],
+            black_to_play: 0,
+            castling: [[0; 2]; 2],
+            en_passant: [0; BOARD_DIMENSION],
+        };
+
+        // The seed is arbitrary, but must never change so hashes stay stable between builds.
+        let mut state = 1_070_372;
+        let mut key;
+
+        let mut color = 0;
+        while color < 2 {
+            let mut piece_type = 0;
+            while piece_type < 6 {
+                let mut square = 0;
+                while square < SQUARE_COUNT {
+                    (state, key) = xorshift64_star(state);
+                    keys.pieces[color][piece_type][square] = key;
+                    square += 1;
+                }
+                piece_type += 1;
+            }
+
+            let mut side = 0;
+            while side < 2 {
+                (state, key) = xorshift64_star(state);
+                keys.castling[color][side] = key;
+                side += 1;
+            }
+            color += 1;
+        }
+
+        let mut file = 0;
+        while file < BOARD_DIMENSION {
+            (state, key) = xorshift64_star(state);
+            keys.en_passant[file] = key;
+            file += 1;
+        }
+
+        (_, key) = xorshift64_star(state);
+        keys.black_to_play = key;
+
+        keys
+    }
+}
+
+static KEYS: ZobristKeys = ZobristKeys::new();
+
+pub(crate) fn piece_key(piece: Piece, index: usize) -> u64 {
+    KEYS.pieces[piece.color.index()][piece.piece_type.index()][index]
+}
+
+pub(crate) fn black_to_play_key() -> u64 {
+    KEYS.black_to_play
+}
+
+pub(crate) fn castle_key(color: Color, state: CastleState) -> u64 {
+    let [kingside, queenside] = KEYS.castling[color.index()];
+    match state {
+        CastleState::Kingside => kingside,
+        CastleState::Queenside => queenside,
+        CastleState::Both => kingside ^ queenside,
+        CastleState::None => 0,
+    }
+}
+
+impl Board {
+    /// Key of an en-passant square, only counted when a pawn can actually capture there so
+    /// positions that only differ by an unusable en-passant square hash the same.
+    pub(crate) fn en_passant_key(&self, square: &Square) -> u64 {
+        let capturer = if square.row() == WHITE_PAWN_ROW + 1 {
+            Color::Black
+        } else {
+            Color::White
+        };
+        let capturers = attacks::pawn_attacks(capturer.opposite(), square.index())
+            & self.pieces_of(PieceType::Pawn, capturer);
+
+        if capturers.is_empty() {
+            0
+        } else {
+            KEYS.en_passant[usize::from(square.col())]
+        }
+    }
+
+    /// Computes the hash of the position from scratch.
+    pub(crate) fn compute_hash(&self) -> u64 {
+        let mut hash = 0;
+
+        for index in self.occupied() {
+            hash ^= piece_key(self.piece_at(index).unwrap(), index);
+        }
+
+        if self.to_play == Color::Black {
+            hash ^= black_to_play_key();
+        }
+
+        for color in &[Color::White, Color::Black] {
+            hash ^= castle_key(*color, self.get_castle_state(*color));
+        }
+
+        if let Some(square) = &self.en_passant_square {
+            hash ^= self.en_passant_key(square);
+        }
+
+        hash
+    }
+}
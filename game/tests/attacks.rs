@@ -0,0 +1,109 @@
+mod attacks {
+    use game::{Board, Color, Square};
+
+    fn squares(squares: &[Square]) -> Vec<String> {
+        squares.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn sq(notation: &str) -> Square {
+        Square::from_notation(notation).unwrap()
+    }
+
+    #[test]
+    fn pawns_attack_forward_only() {
+        let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            squares(&board.attackers_of(&sq("d5"), Color::White)),
+            ["e4"]
+        );
+        assert_eq!(
+            squares(&board.attackers_of(&sq("e4"), Color::Black)),
+            ["d5"]
+        );
+        assert!(board.attackers_of(&sq("d3"), Color::White).is_empty());
+        assert!(board.is_square_attacked(&sq("c4"), Color::Black));
+        assert!(!board.is_square_attacked(&sq("c6"), Color::Black));
+    }
+
+    #[test]
+    fn kings_attack_adjacent_squares() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            squares(&board.attackers_of(&sq("d2"), Color::White)),
+            ["e1"]
+        );
+        assert!(board.is_square_attacked(&sq("d7"), Color::Black));
+        assert!(!board.is_square_attacked(&sq("e6"), Color::Black));
+    }
+
+    #[test]
+    fn sliders_stop_at_the_first_piece() {
+        let board = Board::from_fen("4k3/8/8/8/R1N4r/8/8/4K3 w - - 0 1").unwrap();
+        assert!(board.is_square_attacked(&sq("c4"), Color::Black));
+        assert!(!board.is_square_attacked(&sq("b4"), Color::Black));
+
+        // Defending a piece counts as attacking its square.
+        assert_eq!(
+            squares(&board.attackers_of(&sq("c4"), Color::White)),
+            ["a4"]
+        );
+    }
+
+    #[test]
+    fn every_attacker_is_listed() {
+        let board = Board::from_fen("4k3/8/8/4p3/3P4/5N2/8/4QK2 w - - 0 1").unwrap();
+        assert_eq!(
+            squares(&board.attackers_of(&sq("e5"), Color::White)),
+            ["e1", "f3", "d4"]
+        );
+    }
+
+    #[test]
+    fn checkers() {
+        let board = Board::from_fen(include_str!("boards/starting.fen")).unwrap();
+        assert!(board.checkers().is_empty());
+
+        let board = Board::from_fen("4k3/8/8/8/1b6/8/8/r3K3 w - - 0 1").unwrap();
+        assert_eq!(squares(&board.checkers()), ["a1", "b4"]);
+    }
+
+    #[test]
+    fn pinned_pieces() {
+        let board = Board::from_fen("4k3/4r3/8/8/1b6/8/3NR3/4K3 w - - 0 1").unwrap();
+        let pins = board.pinned_pieces(Color::White);
+        assert_eq!(pins.len(), 2);
+
+        assert_eq!(pins[0].pinned.to_string(), "d2");
+        assert_eq!(pins[0].pinner.to_string(), "b4");
+        assert_eq!(squares(&pins[0].ray), ["d2", "c3", "b4"]);
+
+        assert_eq!(pins[1].pinned.to_string(), "e2");
+        assert_eq!(pins[1].pinner.to_string(), "e7");
+        assert_eq!(squares(&pins[1].ray), ["e2", "e3", "e4", "e5", "e6", "e7"]);
+
+        // The rooks pin each other.
+        let pins = board.pinned_pieces(Color::Black);
+        assert_eq!(pins.len(), 1);
+        assert_eq!(pins[0].pinned.to_string(), "e7");
+        assert_eq!(pins[0].pinner.to_string(), "e2");
+    }
+
+    #[test]
+    fn two_blockers_are_not_pinned() {
+        let board = Board::from_fen("4k3/4r3/8/8/4p3/8/4R3/4K3 w - - 0 1").unwrap();
+        assert!(board.pinned_pieces(Color::White).is_empty());
+    }
+
+    #[test]
+    fn pinned_pieces_move_along_the_ray() {
+        let board = Board::from_fen("4k3/4r3/8/8/1b6/8/3NR3/4K3 w - - 0 1").unwrap();
+        assert!(board.legal_moves_from(&sq("d2")).is_empty());
+
+        let rook_moves: Vec<_> = board
+            .legal_moves_from(&sq("e2"))
+            .iter()
+            .map(|mv| mv.dst.to_string())
+            .collect();
+        assert_eq!(rook_moves, ["e3", "e4", "e5", "e6", "e7"]);
+    }
+}
@@ -0,0 +1,133 @@
+mod chess960 {
+    use std::collections::HashSet;
+
+    use game::{board_with_chess960_setup, board_with_setup, Board, MoveError, MoveKind};
+
+    fn play(fen: &str, src: &str, dst: &str) -> Result<String, MoveError> {
+        let board = Board::from_fen(fen).unwrap();
+        board
+            .move_notation(src, dst, None)
+            .map(|(board, _)| board.to_fen())
+    }
+
+    #[test]
+    fn numbered_setups() {
+        let classical = board_with_chess960_setup(518).unwrap();
+        assert_eq!(classical.to_fen(), board_with_setup().to_fen());
+        assert_eq!(classical.hash(), board_with_setup().hash());
+
+        assert_eq!(
+            board_with_chess960_setup(0).unwrap().to_fen(),
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+        );
+        assert_eq!(
+            board_with_chess960_setup(959).unwrap().to_fen(),
+            "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/RKRNNQBB w KQkq - 0 1"
+        );
+        assert!(board_with_chess960_setup(960).is_none());
+    }
+
+    #[test]
+    fn setups_are_distinct_and_legal() {
+        let mut rows = HashSet::new();
+        for n in 0..960 {
+            let board = board_with_chess960_setup(n).unwrap();
+            assert!(board.validate_position().is_ok(), "{}", n);
+
+            let fen = board.to_fen();
+            let white_row = fen.split(['/', ' ']).nth(7).unwrap();
+            assert!(rows.insert(white_row.to_string()), "{}", n);
+        }
+        assert_eq!(rows.len(), 960);
+    }
+
+    #[test]
+    fn castling_is_the_king_taking_its_rook() {
+        let board = Board::from_fen(include_str!("boards/chess960_inner_rook.fen")).unwrap();
+        let castles: Vec<_> = board
+            .legal_moves()
+            .into_iter()
+            .filter(|mv| mv.is_castle())
+            .collect();
+        assert_eq!(castles.len(), 1);
+        assert_eq!(castles[0].to_uci(), "e1g1");
+        assert_eq!(castles[0].kind, MoveKind::KingsideCastle);
+        assert_eq!(castles[0].captured, None);
+    }
+
+    #[test]
+    fn king_and_rook_swap_squares() {
+        let fen = "4k3/8/8/8/8/8/8/1R3KR1 w GB - 0 1";
+        assert_eq!(
+            play(fen, "f1", "g1"),
+            Ok(String::from("4k3/8/8/8/8/8/8/1R3RK1 b - - 1 1"))
+        );
+        assert_eq!(
+            play(fen, "f1", "b1"),
+            Ok(String::from("4k3/8/8/8/8/8/8/2KR2R1 b - - 1 1"))
+        );
+    }
+
+    #[test]
+    fn classical_squares_castle_by_two_squares() {
+        // Castling from the classical squares keeps the usual notation, even in Chess960.
+        let board = board_with_chess960_setup(518).unwrap();
+        let board = Board::from_fen(board.to_fen().replace("NBQKBN", "3K2")).unwrap();
+        let castles: Vec<_> = board
+            .legal_moves()
+            .into_iter()
+            .filter(|mv| mv.is_castle())
+            .map(|mv| mv.to_uci())
+            .collect();
+        assert_eq!(castles, ["e1g1", "e1c1"]);
+    }
+
+    #[test]
+    fn king_can_take_on_the_rook_square() {
+        // The rook was taken, the king can take back without castling.
+        assert_eq!(
+            play("4k3/8/8/8/8/8/8/5Kb1 w G - 0 1", "f1", "g1"),
+            Ok(String::from("4k3/8/8/8/8/8/8/6K1 b - - 0 1"))
+        );
+    }
+
+    #[test]
+    fn king_already_on_its_castled_square() {
+        assert_eq!(
+            play("4k3/8/8/8/8/8/8/R1K5 w A - 0 1", "c1", "a1"),
+            Ok(String::from("4k3/8/8/8/8/8/8/2KR4 b - - 1 1"))
+        );
+    }
+
+    #[test]
+    fn rook_destination_must_be_empty() {
+        assert_eq!(
+            play("4k3/8/8/8/8/8/8/RK1N4 w A - 0 1", "b1", "a1"),
+            Err(MoveError::PathObstructed)
+        );
+    }
+
+    #[test]
+    fn castling_rook_does_not_shield_the_king() {
+        // The rook on a1 sees the king's path once the castling rook has left b1.
+        assert_eq!(
+            play("4k3/8/8/8/8/8/8/rR3K2 w B - 0 1", "f1", "b1"),
+            Err(MoveError::CannotCastleThroughCheck)
+        );
+    }
+
+    #[test]
+    fn taking_own_rook_without_rights() {
+        // Without castling rights, moving onto the rook is taking one's own piece.
+        assert_eq!(
+            play("4k3/8/8/8/8/8/8/5KR1 w - - 0 1", "f1", "g1"),
+            Err(MoveError::DestinationObstructed)
+        );
+
+        // The right to castle with the other rook doesn't make it a castling move either.
+        assert_eq!(
+            play("4k3/8/8/8/8/8/8/R4KR1 w A - 0 1", "f1", "g1"),
+            Err(MoveError::DestinationObstructed)
+        );
+    }
+}
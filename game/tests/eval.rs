@@ -0,0 +1,113 @@
+macro_rules! symmetric {
+    ($name:ident, $position: ident) => {
+        #[test]
+        fn $name() {
+            const FEN_POSITION: &str =
+                include_str!(concat!("boards/", stringify!($position), ".fen"));
+            let board = Board::from_fen(FEN_POSITION).unwrap();
+            let flipped = Board::from_fen(flip(FEN_POSITION)).unwrap();
+            assert_eq!(board.evaluate(), flipped.evaluate(), "{}", flipped.to_fen());
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod eval {
+    use game::{board_with_setup, Board};
+
+    /// Mirrors a FEN position vertically and swaps the colors, giving the same position from
+    /// the other side's point of view.
+    fn flip(fen: &str) -> String {
+        let swap_case = |s: &str| -> String {
+            s.chars()
+                .map(|c| {
+                    if c.is_ascii_uppercase() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c.to_ascii_uppercase()
+                    }
+                })
+                .collect()
+        };
+
+        let fields: Vec<_> = fen.split_whitespace().collect();
+        let rows: Vec<_> = fields[0].split('/').rev().map(swap_case).collect();
+        let to_play = if fields[1] == "w" { "b" } else { "w" };
+        let en_passant = match fields[3] {
+            "-" => String::from("-"),
+            square => {
+                let row = 9 - square[1..].parse::<u32>().unwrap();
+                format!("{}{}", &square[..1], row)
+            }
+        };
+        format!(
+            "{} {} {} {} {} {}",
+            rows.join("/"),
+            to_play,
+            swap_case(fields[2]),
+            en_passant,
+            fields[4],
+            fields[5]
+        )
+    }
+
+    fn evaluate(fen: &str) -> i32 {
+        Board::from_fen(fen).unwrap().evaluate()
+    }
+
+    symmetric!(starting_symmetric, starting);
+    symmetric!(kiwipete_symmetric, kiwipete);
+    symmetric!(en_passant_symmetric, en_passant);
+    symmetric!(position_3_symmetric, perft_position_3);
+    symmetric!(position_4_symmetric, perft_position_4);
+    symmetric!(position_5_symmetric, perft_position_5);
+    symmetric!(position_6_symmetric, perft_position_6);
+    symmetric!(bishops_symmetric, opposite_colored_bishops);
+
+    #[test]
+    fn mirrored_fixtures_agree() {
+        let board = Board::from_fen(include_str!("boards/perft_position_4.fen")).unwrap();
+        let mirrored =
+            Board::from_fen(include_str!("boards/perft_position_4_mirrored.fen")).unwrap();
+        assert_eq!(board.evaluate(), mirrored.evaluate());
+    }
+
+    #[test]
+    fn starting_position_is_balanced() {
+        assert_eq!(board_with_setup().evaluate(), 0);
+    }
+
+    #[test]
+    fn score_is_from_the_side_to_play() {
+        let white = evaluate("4k3/8/8/8/8/8/8/3QK3 w - - 0 1");
+        let black = evaluate("4k3/8/8/8/8/8/8/3QK3 b - - 0 1");
+        assert!(white > 800, "{}", white);
+        assert_eq!(white, -black);
+    }
+
+    #[test]
+    fn material_counts() {
+        let no_queen = evaluate("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let no_rook = evaluate("1nbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Kkq - 0 1");
+        let no_knight = evaluate("r1bqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let no_pawn = evaluate("rnbqkbnr/ppp1pppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert!(no_queen > no_rook, "{} {}", no_queen, no_rook);
+        assert!(no_rook > no_knight, "{} {}", no_rook, no_knight);
+        assert!(no_knight > no_pawn, "{} {}", no_knight, no_pawn);
+        assert!(no_pawn > 0, "{}", no_pawn);
+    }
+
+    #[test]
+    fn king_centralizes_in_the_endgame() {
+        // With every piece on, the king is safer on its first row...
+        let castled = evaluate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQ1RK1 w kq - 0 1");
+        let central = evaluate("rnbqkbnr/pppppppp/8/8/4K3/8/PPPPPPPP/RNBQ1R2 w kq - 0 1");
+        assert!(castled > central, "{} {}", castled, central);
+
+        // ...but belongs in the center once they are traded.
+        let castled = evaluate("4k3/8/8/8/8/8/5PPP/6K1 w - - 0 1");
+        let central = evaluate("4k3/8/8/8/4K3/8/5PPP/8 w - - 0 1");
+        assert!(central > castled, "{} {}", central, castled);
+    }
+}
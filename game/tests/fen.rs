@@ -0,0 +1,235 @@
+macro_rules! round_trip {
+    ($name:ident, $position: ident) => {
+        #[test]
+        fn $name() {
+            const FEN_POSITION: &str =
+                include_str!(concat!("boards/", stringify!($position), ".fen"));
+            let board = Board::from_fen(FEN_POSITION).unwrap();
+            assert_eq!(board.to_fen(), FEN_POSITION.trim());
+        }
+    };
+}
+
+macro_rules! illegal {
+    ($name:ident, $fen: expr, $exp_error: pat) => {
+        #[test]
+        fn $name() {
+            // Lenient parsing accepts the position, strict parsing rejects it.
+            assert!(Board::from_fen($fen).is_ok());
+            let err = Board::from_fen_with_mode($fen, FenMode::Strict)
+                .err()
+                .unwrap();
+            assert!(matches!(err, $exp_error), "{:?}", err);
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod fen {
+    use game::{Board, Color, FenError, FenMode};
+
+    round_trip!(starting_round_trip, starting);
+    round_trip!(kiwipete_round_trip, kiwipete);
+    round_trip!(en_passant_round_trip, en_passant);
+    round_trip!(no_castling_round_trip, en_passant_check_evasion);
+    round_trip!(x_fen_round_trip, chess960_inner_rook);
+
+    #[test]
+    fn en_passant_square_is_behind_pawn() {
+        let board = Board::from_fen(include_str!("boards/starting.fen")).unwrap();
+        let (board, _) = board.move_notation("e2", "e4", None).unwrap();
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+
+        let (board, _) = board.move_notation("d7", "d5", None).unwrap();
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2"
+        );
+    }
+
+    #[test]
+    fn too_many_rows() {
+        for mode in &[FenMode::Lenient, FenMode::Strict] {
+            let err = Board::from_fen_with_mode("8/8/8/8/8/8/8/8/8 w - - 0 1", *mode)
+                .err()
+                .unwrap();
+            assert!(matches!(err, FenError::TooManyRows), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn en_passant_square_on_wrong_rank() {
+        let err = Board::from_fen("4k3/8/8/8/4pP2/8/8/4K3 b - f4 0 1")
+            .err()
+            .unwrap();
+        assert!(matches!(err, FenError::InvalidEnPassantRank));
+    }
+
+    #[test]
+    fn castling_rights_follow_moves() {
+        let board = Board::from_fen(include_str!("boards/castling.fen")).unwrap();
+
+        // Moving a rook loses castling on its side only.
+        let (board, _) = board.move_notation("h1", "g1", None).unwrap();
+        assert_eq!(
+            board.to_fen(),
+            "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K1R1 b Qkq - 1 1"
+        );
+
+        // Moving the king loses both.
+        let (board, _) = board.move_notation("e8", "d8", None).unwrap();
+        assert_eq!(
+            board.to_fen(),
+            "r2k3r/pppppppp/8/8/8/8/PPPPPPPP/R3K1R1 w Q - 2 2"
+        );
+    }
+
+    #[test]
+    fn castling_rights_without_rook() {
+        // Rights listed in the FEN are kept as-is, but castling still needs the rook.
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w KQ - 0 1").unwrap();
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 w KQ - 0 1");
+        assert!(board.move_notation("e1", "g1", None).is_err());
+    }
+
+    #[test]
+    fn shredder_fen_castling() {
+        // Shredder-FEN gives the file of every castling rook, X-FEN only when it isn't the
+        // outermost one.
+        let shredder =
+            Board::from_fen("rn2k1r1/ppp1pp1p/3p2p1/5bn1/P7/2N2B2/1PPPPP2/2BNK1RR w Gga - 4 11")
+                .unwrap();
+        let x_fen = Board::from_fen(include_str!("boards/chess960_inner_rook.fen")).unwrap();
+        assert_eq!(shredder.to_fen(), x_fen.to_fen());
+        assert_eq!(shredder.hash(), x_fen.hash());
+
+        let shredder = Board::from_fen(include_str!("boards/chess960_position_1.fen")).unwrap();
+        assert_eq!(
+            shredder.to_fen(),
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+        );
+    }
+
+    #[test]
+    fn shredder_fen_classical_castling() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1").unwrap();
+        assert_eq!(board.to_fen(), "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    }
+
+    illegal!(
+        missing_king,
+        "8/8/8/8/8/8/8/4K3 w - - 0 1",
+        FenError::MissingKing {
+            color: Color::Black
+        }
+    );
+    illegal!(
+        two_kings,
+        "4k3/8/8/8/8/8/8/K3K3 w - - 0 1",
+        FenError::TooManyKings {
+            color: Color::White
+        }
+    );
+    illegal!(
+        pawn_on_first_rank,
+        "4k3/8/8/8/8/8/8/P3K3 w - - 0 1",
+        FenError::PawnOnBackRank { .. }
+    );
+    illegal!(
+        pawn_on_last_rank,
+        "p3k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        FenError::PawnOnBackRank { .. }
+    );
+    illegal!(
+        too_many_pawns,
+        "4k3/8/8/8/8/P7/PPPPPPPP/4K3 w - - 0 1",
+        FenError::TooManyPawns {
+            color: Color::White
+        }
+    );
+    illegal!(
+        side_not_to_move_in_check,
+        "4k3/8/8/8/8/8/8/4K2r b - - 0 1",
+        FenError::OpponentInCheck
+    );
+    illegal!(
+        triple_check,
+        "4k3/8/8/8/1b6/5n2/8/r3K3 w - - 0 1",
+        FenError::TooManyCheckers
+    );
+    illegal!(
+        castling_without_rook,
+        "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+        FenError::InvalidCastlingRights {
+            color: Color::White
+        }
+    );
+    illegal!(
+        castling_with_moved_king,
+        "r3k2r/8/8/8/8/8/4K3/R6R w KQ - 0 1",
+        FenError::InvalidCastlingRights { .. }
+    );
+    illegal!(
+        castling_rook_on_wrong_side,
+        "4k3/8/8/8/8/8/8/R6K w K - 0 1",
+        FenError::InvalidCastlingRights {
+            color: Color::White
+        }
+    );
+    illegal!(
+        castling_field_with_junk,
+        "r3k2r/8/8/8/8/8/8/R3K2R w KZ - 0 1",
+        FenError::InvalidCastlingField
+    );
+    illegal!(
+        castling_field_with_dash_and_rights,
+        "r3k2r/8/8/8/8/8/8/R3K2R w K- - 0 1",
+        FenError::InvalidCastlingField
+    );
+    illegal!(
+        en_passant_without_pawn,
+        "4k3/8/8/8/8/8/8/4K3 b - e3 0 1",
+        FenError::EnPassantWithoutPawn
+    );
+
+    #[test]
+    fn fixtures_are_legal() {
+        for fen in &[
+            include_str!("boards/starting.fen"),
+            include_str!("boards/kiwipete.fen"),
+            include_str!("boards/en_passant.fen"),
+            include_str!("boards/en_passant_check_evasion.fen"),
+            include_str!("boards/castling.fen"),
+            include_str!("boards/checkmate.fen"),
+            include_str!("boards/stalemate.fen"),
+            include_str!("boards/perft_position_3.fen"),
+            include_str!("boards/perft_position_4.fen"),
+            include_str!("boards/perft_position_5.fen"),
+            include_str!("boards/perft_position_6.fen"),
+            include_str!("boards/chess960_position_1.fen"),
+            include_str!("boards/chess960_position_3.fen"),
+        ] {
+            let board = Board::from_fen_with_mode(fen, FenMode::Strict).unwrap();
+            assert!(board.validate_position().is_ok());
+        }
+    }
+
+    #[test]
+    fn lenient_positions_do_not_panic() {
+        for fen in &[
+            "8/8/8/8/8/8/8/8 w KQkq - 0 1",
+            "P3k3/8/8/8/8/8/8/p3K3 w - - 0 1",
+            "p3k3/8/8/8/8/8/8/P3K3 b - - 0 1",
+            "4k3/8/8/8/8/8/8/K3K3 w KQ - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+            board.legal_moves();
+            board.status();
+            board.to_fen();
+        }
+    }
+}
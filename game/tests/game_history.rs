@@ -0,0 +1,95 @@
+mod game_history {
+    use game::{Board, Game, Move, MoveError, MoveKind, PieceType};
+
+    fn play(game: &mut Game, src: &str, dst: &str) {
+        game.play(&Move::from_notation(src, dst, None).unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn new_game_starts_from_setup() {
+        let game = Game::new();
+        assert_eq!(game.position().to_fen(), game::board_with_setup().to_fen());
+        assert_eq!(game.moves().count(), 0);
+        assert_eq!(game.positions().count(), 1);
+    }
+
+    #[test]
+    fn play_records_moves_and_positions() {
+        let mut game = Game::new();
+        play(&mut game, "e2", "e4");
+        play(&mut game, "e7", "e5");
+
+        let moves: Vec<_> = game.moves().map(|mv| mv.dst.to_string()).collect();
+        assert_eq!(moves, vec!["e4", "e5"]);
+        assert_eq!(game.positions().count(), 3);
+        assert_eq!(
+            game.positions().last().unwrap().to_fen(),
+            game.position().to_fen()
+        );
+    }
+
+    #[test]
+    fn illegal_move_leaves_game_untouched() {
+        let mut game = Game::new();
+        play(&mut game, "e2", "e4");
+
+        let err = game
+            .play(&Move::from_notation("e4", "e6", None).unwrap())
+            .err()
+            .unwrap();
+        assert_eq!(err, MoveError::InvalidMove);
+        assert_eq!(game.moves().count(), 1);
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut game = Game::new();
+        let start_fen = game.position().to_fen();
+        play(&mut game, "e2", "e4");
+        let after_fen = game.position().to_fen();
+
+        assert_eq!(game.undo().unwrap().position.to_fen(), after_fen);
+        assert_eq!(game.position().to_fen(), start_fen);
+        assert!(game.undo().is_none());
+
+        game.redo().unwrap();
+        assert_eq!(game.position().to_fen(), after_fen);
+        assert!(game.redo().is_none());
+    }
+
+    #[test]
+    fn playing_after_undo_discards_redo() {
+        let mut game = Game::new();
+        play(&mut game, "e2", "e4");
+        game.undo().unwrap();
+        play(&mut game, "d2", "d4");
+
+        assert!(game.redo().is_none());
+        let moves: Vec<_> = game.moves().map(|mv| mv.dst.to_string()).collect();
+        assert_eq!(moves, vec!["d4"]);
+    }
+
+    #[test]
+    fn captures_are_recorded() {
+        let mut game = Game::new();
+        play(&mut game, "e2", "e4");
+        play(&mut game, "d7", "d5");
+        play(&mut game, "e4", "d5");
+
+        let history = game.history();
+        assert!(history[0].mv.captured.is_none());
+        assert_eq!(history[2].mv.captured, Some(PieceType::Pawn));
+    }
+
+    #[test]
+    fn en_passant_capture_is_recorded() {
+        let board = Board::from_fen(include_str!("boards/en_passant.fen")).unwrap();
+        let mut game = Game::from_position(board);
+        play(&mut game, "e4", "f3");
+
+        let entry = &game.history()[0];
+        assert_eq!(entry.mv.captured, Some(PieceType::Pawn));
+        assert_eq!(entry.mv.kind, MoveKind::EnPassant);
+    }
+}
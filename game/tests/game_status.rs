@@ -0,0 +1,175 @@
+macro_rules! status {
+    ($name:ident, $position: ident, $exp_status: expr) => {
+        #[test]
+        fn $name() {
+            const FEN_POSITION: &str =
+                include_str!(concat!("boards/", stringify!($position), ".fen"));
+            let board = Board::from_fen(FEN_POSITION).unwrap();
+            assert_eq!(board.status(), $exp_status);
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod game_status {
+    use game::{Board, Color, DrawReason, Game, GameStatus, Move};
+
+    status!(starting_is_ongoing, starting, GameStatus::Ongoing);
+    status!(
+        fools_mate,
+        checkmate,
+        GameStatus::Checkmate {
+            winner: Color::Black
+        }
+    );
+    status!(stalemate, stalemate, GameStatus::Stalemate);
+
+    #[test]
+    fn check_is_detected() {
+        let board = Board::from_fen(include_str!("boards/checkmate.fen")).unwrap();
+        assert!(board.is_check());
+
+        let board = Board::from_fen(include_str!("boards/stalemate.fen")).unwrap();
+        assert!(!board.is_check());
+    }
+
+    #[test]
+    fn check_is_not_mate_when_king_can_escape() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+        assert!(board.is_check());
+        assert_eq!(board.status(), GameStatus::Ongoing);
+    }
+
+    #[test]
+    fn seventy_five_move_rule() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w - - 150 100").unwrap();
+        assert_eq!(
+            board.status(),
+            GameStatus::Draw {
+                reason: DrawReason::SeventyFiveMoveRule
+            }
+        );
+    }
+
+    #[test]
+    fn mate_takes_precedence_over_seventy_five_move_rule() {
+        let board = Board::from_fen("R3k3/8/4K3/8/8/8/8/8 b - - 150 100").unwrap();
+        assert_eq!(
+            board.status(),
+            GameStatus::Checkmate {
+                winner: Color::White
+            }
+        );
+    }
+
+    status!(
+        king_against_king,
+        king_against_king,
+        GameStatus::Draw {
+            reason: DrawReason::InsufficientMaterial
+        }
+    );
+    status!(
+        king_and_bishop_against_king,
+        king_and_bishop_against_king,
+        GameStatus::Draw {
+            reason: DrawReason::InsufficientMaterial
+        }
+    );
+    status!(
+        king_and_knight_against_king,
+        king_and_knight_against_king,
+        GameStatus::Draw {
+            reason: DrawReason::InsufficientMaterial
+        }
+    );
+    status!(
+        same_colored_bishops,
+        same_colored_bishops,
+        GameStatus::Draw {
+            reason: DrawReason::InsufficientMaterial
+        }
+    );
+    status!(
+        opposite_colored_bishops,
+        opposite_colored_bishops,
+        GameStatus::Ongoing
+    );
+    status!(two_knights, two_knights, GameStatus::Ongoing);
+
+    #[test]
+    fn fifty_move_rule_is_claimable() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w - - 99 80").unwrap();
+        assert_eq!(board.claimable_draw(), None);
+
+        let (board, _) = board.move_notation("h1", "h2", None).unwrap();
+        assert_eq!(board.claimable_draw(), Some(DrawReason::FiftyMoveRule));
+        assert_eq!(board.status(), GameStatus::Ongoing);
+    }
+
+    fn shuffle_knights(game: &mut Game, times: usize) {
+        for _ in 0..times {
+            for (src, dst) in &[("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")] {
+                game.play(&Move::from_notation(src, dst, None).unwrap())
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn threefold_repetition_is_claimable() {
+        let mut game = Game::new();
+        shuffle_knights(&mut game, 1);
+        assert_eq!(game.repetitions(), 2);
+        assert_eq!(game.claimable_draw(), None);
+        assert_eq!(game.claim_draw(), None);
+
+        shuffle_knights(&mut game, 1);
+        assert_eq!(game.repetitions(), 3);
+        assert_eq!(game.status(), GameStatus::Ongoing);
+        assert_eq!(game.claimable_draw(), Some(DrawReason::ThreefoldRepetition));
+
+        assert_eq!(game.claim_draw(), Some(DrawReason::ThreefoldRepetition));
+        assert_eq!(
+            game.status(),
+            GameStatus::Draw {
+                reason: DrawReason::ThreefoldRepetition
+            }
+        );
+
+        // Taking back the last move cancels the claim.
+        game.undo();
+        assert_eq!(game.status(), GameStatus::Ongoing);
+    }
+
+    #[test]
+    fn fivefold_repetition_ends_the_game() {
+        let mut game = Game::new();
+        shuffle_knights(&mut game, 3);
+        assert_eq!(game.status(), GameStatus::Ongoing);
+
+        shuffle_knights(&mut game, 1);
+        assert_eq!(game.repetitions(), 5);
+        assert_eq!(
+            game.status(),
+            GameStatus::Draw {
+                reason: DrawReason::FivefoldRepetition
+            }
+        );
+        assert_eq!(game.claimable_draw(), None);
+    }
+
+    #[test]
+    fn lost_castling_rights_break_repetition() {
+        let mut game =
+            Game::from_position(Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap());
+        for (src, dst) in &[("e1", "f1"), ("e8", "f8"), ("f1", "e1"), ("f8", "e8")] {
+            game.play(&Move::from_notation(src, dst, None).unwrap())
+                .unwrap();
+        }
+
+        // Same pieces on the same squares, but neither side can castle anymore.
+        assert_eq!(game.repetitions(), 1);
+    }
+}
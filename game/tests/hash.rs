@@ -0,0 +1,88 @@
+macro_rules! incremental_matches_recompute {
+    ($name:ident, $position: ident) => {
+        #[test]
+        fn $name() {
+            let board = Board::from_fen(include_str!(concat!(
+                "boards/",
+                stringify!($position),
+                ".fen"
+            )))
+            .unwrap();
+            check_tree(&board, 2);
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod hash {
+    use game::{board_with_setup, Board};
+
+    /// Walks every line up to a depth, checking the hash kept up to date while playing moves
+    /// matches the one computed from scratch when loading the same position.
+    fn check_tree(board: &Board, depth: usize) {
+        let reloaded = Board::from_fen(board.to_fen()).unwrap();
+        assert_eq!(board.hash(), reloaded.hash(), "{}", board.to_fen());
+
+        if depth == 0 {
+            return;
+        }
+        for mv in board.legal_moves() {
+            let (next, _) = board.move_piece(&mv).unwrap();
+            check_tree(&next, depth - 1);
+        }
+    }
+
+    fn play(board: &Board, moves: &[(&str, &str)]) -> Board {
+        moves.iter().fold(board.clone(), |board, (src, dst)| {
+            board.move_notation(src, dst, None).unwrap().0
+        })
+    }
+
+    incremental_matches_recompute!(starting_incremental, starting);
+    incremental_matches_recompute!(kiwipete_incremental, kiwipete);
+    incremental_matches_recompute!(en_passant_incremental, en_passant);
+    incremental_matches_recompute!(promotion_incremental, perft_position_4);
+    incremental_matches_recompute!(chess960_incremental, chess960_position_1);
+
+    #[test]
+    fn setup_matches_fen() {
+        let board = Board::from_fen(include_str!("boards/starting.fen")).unwrap();
+        assert_eq!(board_with_setup().hash(), board.hash());
+    }
+
+    #[test]
+    fn transpositions_hash_the_same() {
+        let start = board_with_setup();
+        let a = play(&start, &[("g1", "f3"), ("g8", "f6"), ("b1", "c3")]);
+        let b = play(&start, &[("b1", "c3"), ("g8", "f6"), ("g1", "f3")]);
+        assert_eq!(a.hash(), b.hash());
+
+        // Moving the knights back and forth comes back to the start, clocks aside.
+        let back = play(
+            &start,
+            &[("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")],
+        );
+        assert_eq!(back.hash(), start.hash());
+    }
+
+    #[test]
+    fn state_is_part_of_the_hash() {
+        let white = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        let black = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R b KQ - 0 1").unwrap();
+        let no_castle = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+        let clocks = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 12 40").unwrap();
+        assert_ne!(white.hash(), black.hash());
+        assert_ne!(white.hash(), no_castle.hash());
+        assert_eq!(white.hash(), clocks.hash());
+
+        let en_passant = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let no_en_passant = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_ne!(en_passant.hash(), no_en_passant.hash());
+
+        // An en-passant square no pawn can capture on doesn't change the position.
+        let unusable = Board::from_fen("4k3/8/8/3p3P/8/8/8/4K3 w - d6 0 1").unwrap();
+        let no_en_passant = Board::from_fen("4k3/8/8/3p3P/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(unusable.hash(), no_en_passant.hash());
+    }
+}
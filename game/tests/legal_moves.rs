@@ -0,0 +1,142 @@
+macro_rules! move_count {
+    ($name:ident, $position: ident, $count: expr) => {
+        #[test]
+        fn $name() {
+            const FEN_POSITION: &str =
+                include_str!(concat!("boards/", stringify!($position), ".fen"));
+            let board = Board::from_fen(FEN_POSITION).unwrap();
+            assert_eq!(board.legal_moves().len(), $count);
+        }
+    };
+}
+
+macro_rules! agrees_with_validation {
+    ($name:ident, $position: ident) => {
+        #[test]
+        fn $name() {
+            const FEN_POSITION: &str =
+                include_str!(concat!("boards/", stringify!($position), ".fen"));
+            let board = Board::from_fen(FEN_POSITION).unwrap();
+            let legal_moves = board.legal_moves();
+
+            // Brute-force every square pair and make sure move generation finds the same moves.
+            let mut accepted_moves = Vec::new();
+            for src in Square::ALL.iter() {
+                for dst in Square::ALL.iter() {
+                    for promotion in &[None, Some(PieceType::Queen)] {
+                        let mv = Move::new(*src, *dst, *promotion);
+                        if board.move_piece(&mv).is_ok() {
+                            accepted_moves.push(mv);
+                        }
+                    }
+                }
+            }
+
+            for mv in accepted_moves.iter() {
+                assert!(legal_moves.contains(mv), "{:?} was not generated", mv);
+            }
+            for mv in legal_moves.iter() {
+                if mv.promotion.is_none() || mv.promotion == Some(PieceType::Queen) {
+                    assert!(accepted_moves.contains(mv), "{:?} is not legal", mv);
+                }
+            }
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod legal_moves {
+    use game::{Board, Move, PieceType, Square};
+
+    fn moves_from(board: &Board, src: &str) -> Vec<String> {
+        let mut destinations: Vec<_> = board
+            .legal_moves_from(&Square::from_notation(src).unwrap())
+            .into_iter()
+            .map(|mv| mv.dst.to_string())
+            .collect();
+        destinations.sort();
+        destinations
+    }
+
+    move_count!(starting_position, starting, 20);
+    move_count!(starting_position_black, e6, 20 + 10);
+    move_count!(lone_knight, empty_knight, 8 + 5);
+    move_count!(boxed_knight, knight_boxed, 8 + 2);
+
+    agrees_with_validation!(starting_agrees, starting);
+    agrees_with_validation!(pinned_pawn_agrees, pinned_pawn);
+    agrees_with_validation!(en_passant_agrees, en_passant);
+    agrees_with_validation!(castling_agrees, castling);
+    agrees_with_validation!(promotion_agrees, promotion);
+    agrees_with_validation!(check_through_piece_agrees, check_through_piece);
+
+    #[test]
+    fn pinned_pawn_can_only_capture() {
+        let board = Board::from_fen(include_str!("boards/pinned_pawn.fen")).unwrap();
+        assert_eq!(moves_from(&board, "d2"), vec!["c3"]);
+    }
+
+    #[test]
+    fn en_passant_is_generated() {
+        let board = Board::from_fen(include_str!("boards/en_passant.fen")).unwrap();
+        assert_eq!(moves_from(&board, "e4"), vec!["e3", "f3"]);
+    }
+
+    #[test]
+    fn castling_is_generated() {
+        let board = Board::from_fen(include_str!("boards/castling.fen")).unwrap();
+        assert_eq!(moves_from(&board, "e1"), vec!["c1", "d1", "f1", "g1"]);
+    }
+
+    #[test]
+    fn cannot_castle_through_check() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/5q2/8/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(moves_from(&board, "e1"), vec!["d2"]);
+    }
+
+    #[test]
+    fn queenside_castle_ignores_attacked_b_file() {
+        let board = Board::from_fen("1r2k3/8/8/8/8/8/8/R3K3 w Qq - 0 1").unwrap();
+        assert!(moves_from(&board, "e1").contains(&String::from("c1")));
+    }
+
+    #[test]
+    fn cannot_castle_out_of_check() {
+        let board = Board::from_fen("r3k3/8/8/8/8/8/8/R3K1r1 w Qq - 0 1").unwrap();
+        assert_eq!(moves_from(&board, "e1"), vec!["d2", "e2", "f2"]);
+    }
+
+    #[test]
+    fn each_promotion_is_generated() {
+        let board = Board::from_fen(include_str!("boards/promotion.fen")).unwrap();
+        let promotions: Vec<_> = board
+            .legal_moves_from(&Square::from_notation("e7").unwrap())
+            .into_iter()
+            .filter(|mv| mv.dst == Square::from_notation("e8").unwrap())
+            .map(|mv| mv.promotion.unwrap())
+            .collect();
+        assert_eq!(
+            promotions,
+            vec![
+                PieceType::Queen,
+                PieceType::Rook,
+                PieceType::Bishop,
+                PieceType::Knight
+            ]
+        );
+    }
+
+    #[test]
+    fn opponent_pieces_have_no_moves() {
+        let board = Board::from_fen(include_str!("boards/starting.fen")).unwrap();
+        assert!(moves_from(&board, "e7").is_empty());
+        assert!(moves_from(&board, "e4").is_empty());
+    }
+
+    #[test]
+    fn kings_cannot_touch() {
+        let board = Board::from_fen("8/8/8/3k4/8/3K4/8/8 w - - 0 1").unwrap();
+        assert_eq!(moves_from(&board, "d3"), vec!["c2", "c3", "d2", "e2", "e3"]);
+    }
+}
@@ -0,0 +1,54 @@
+macro_rules! make_unmake {
+    ($name:ident, $position: ident) => {
+        #[test]
+        fn $name() {
+            let mut board = Board::from_fen(include_str!(concat!(
+                "boards/",
+                stringify!($position),
+                ".fen"
+            )))
+            .unwrap();
+            check_tree(&mut board, 2);
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod make_unmake {
+    use game::Board;
+
+    /// Walks every line up to a depth, checking that playing a move in place gives the same
+    /// position as `move_piece`, and that taking it back restores the position exactly.
+    fn check_tree(board: &mut Board, depth: usize) {
+        if depth == 0 {
+            return;
+        }
+
+        let fen = board.to_fen();
+        let hash = board.hash();
+        for mv in board.legal_moves() {
+            let (expected, _) = board.move_piece(&mv).unwrap();
+
+            let undo = board.make_move(mv.clone());
+            assert_eq!(board.to_fen(), expected.to_fen(), "{} after {}", fen, mv);
+            assert_eq!(board.hash(), expected.hash(), "{} after {}", fen, mv);
+
+            check_tree(board, depth - 1);
+
+            board.unmake_move(mv.clone(), undo);
+            assert_eq!(board.to_fen(), fen, "{} after taking back {}", fen, mv);
+            assert_eq!(board.hash(), hash, "{} after taking back {}", fen, mv);
+        }
+    }
+
+    make_unmake!(starting, starting);
+    make_unmake!(kiwipete, kiwipete);
+    make_unmake!(en_passant, en_passant);
+    make_unmake!(castling, castling);
+    make_unmake!(promotion, perft_position_4);
+    make_unmake!(promotion_mirrored, perft_position_4_mirrored);
+    make_unmake!(position_5, perft_position_5);
+    make_unmake!(chess960, chess960_position_1);
+    make_unmake!(chess960_inner_rooks, chess960_position_3);
+}
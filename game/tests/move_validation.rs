@@ -1,33 +1,39 @@
 macro_rules! valid {
-    ($name:ident, $position: ident, $src: expr, $dst: expr) => {
+    ($name:ident, $position: ident, $src: expr, $dst: expr, $promotion: expr) => {
         #[test]
         fn $name() {
             const FEN_POSITION: &str =
                 include_str!(concat!("boards/", stringify!($position), ".fen"));
             let board = Board::from_fen(FEN_POSITION).unwrap();
-            board.move_notation($src, $dst).unwrap();
+            board.move_notation($src, $dst, $promotion).unwrap();
         }
     };
+    ($name:ident, $position: ident, $src: expr, $dst: expr) => {
+        valid!($name, $position, $src, $dst, None);
+    };
 }
 
 macro_rules! invalid {
-    ($name:ident, $position:ident, $src: expr, $dst: expr, $exp_err: expr) => {
+    ($name:ident, $position:ident, $src: expr, $dst: expr, $promotion: expr, $exp_err: expr) => {
         #[test]
         fn $name() {
             const FEN_POSITION: &str =
                 include_str!(concat!("boards/", stringify!($position), ".fen"));
             let board = Board::from_fen(FEN_POSITION).unwrap();
-            let pos = board.move_notation($src, $dst);
+            let pos = board.move_notation($src, $dst, $promotion);
             let err = pos.err().unwrap();
             assert_eq!($exp_err, err);
         }
     };
+    ($name:ident, $position:ident, $src: expr, $dst: expr, $exp_err: expr) => {
+        invalid!($name, $position, $src, $dst, None, $exp_err);
+    };
 }
 
 // BEGIN TESTS.
 
 mod move_validation {
-    use game::{Board, MoveError};
+    use game::{Board, Color, MoveError, PieceType, Square};
 
     valid!(cant_check_through_piece, check_through_piece, "f2", "e1");
 
@@ -105,4 +111,110 @@ mod move_validation {
         MoveError::InvalidMove
     );
     valid!(knight_goes_over_pieces, knight_boxed, "e4", "d6");
+
+    // Test promotion.
+    valid!(pawn_promotes_to_queen, promotion, "e7", "e8", Some('q'));
+    valid!(pawn_promotes_to_knight, promotion, "e7", "e8", Some('N'));
+    valid!(pawn_promotes_with_capture, promotion, "e7", "f8", Some('r'));
+    invalid!(
+        pawn_promotion_required,
+        promotion,
+        "e7",
+        "e8",
+        MoveError::PromotionRequired
+    );
+    invalid!(
+        pawn_cant_promote_to_king,
+        promotion,
+        "e7",
+        "e8",
+        Some('k'),
+        MoveError::InvalidPromotion
+    );
+    invalid!(
+        pawn_cant_promote_before_last_rank,
+        starting,
+        "e2",
+        "e4",
+        Some('q'),
+        MoveError::InvalidPromotion
+    );
+    invalid!(
+        only_pawns_promote,
+        promotion,
+        "e1",
+        "e2",
+        Some('q'),
+        MoveError::InvalidPromotion
+    );
+
+    #[test]
+    fn promoted_piece_replaces_pawn() {
+        const FEN_POSITION: &str = include_str!("boards/promotion.fen");
+        let board = Board::from_fen(FEN_POSITION).unwrap();
+        let (new_position, _) = board.move_notation("e7", "e8", Some('n')).unwrap();
+        let piece = new_position
+            .at(&Square::from_notation("e8").unwrap())
+            .unwrap();
+        assert_eq!(piece.piece_type, PieceType::Knight);
+        assert_eq!(piece.color, Color::White);
+    }
+}
+
+mod move_details {
+    use game::{Board, MoveKind, PieceType};
+
+    fn played(fen: &str, src: &str, dst: &str, promotion: Option<char>) -> game::Move {
+        let board = Board::from_fen(fen).unwrap();
+        let (_, mv) = board.move_notation(src, dst, promotion).unwrap();
+        mv
+    }
+
+    #[test]
+    fn quiet_move() {
+        let mv = played(include_str!("boards/starting.fen"), "g1", "f3", None);
+        assert_eq!(mv.piece, Some(PieceType::Knight));
+        assert_eq!(mv.kind, MoveKind::Normal);
+        assert!(!mv.is_capture());
+    }
+
+    #[test]
+    fn double_pawn_push() {
+        let mv = played(include_str!("boards/starting.fen"), "e2", "e4", None);
+        assert_eq!(mv.piece, Some(PieceType::Pawn));
+        assert_eq!(mv.kind, MoveKind::DoublePawnPush);
+    }
+
+    #[test]
+    fn capture() {
+        let mv = played(include_str!("boards/pinned_pawn.fen"), "d2", "c3", None);
+        assert_eq!(mv.captured, Some(PieceType::Bishop));
+        assert_eq!(mv.kind, MoveKind::Normal);
+    }
+
+    #[test]
+    fn en_passant() {
+        let mv = played(include_str!("boards/en_passant.fen"), "e4", "f3", None);
+        assert_eq!(mv.captured, Some(PieceType::Pawn));
+        assert_eq!(mv.kind, MoveKind::EnPassant);
+    }
+
+    #[test]
+    fn castles() {
+        let kingside = played(include_str!("boards/castling.fen"), "e1", "g1", None);
+        assert_eq!(kingside.kind, MoveKind::KingsideCastle);
+        assert!(kingside.is_castle());
+
+        let queenside = played(include_str!("boards/castling.fen"), "e1", "c1", None);
+        assert_eq!(queenside.kind, MoveKind::QueensideCastle);
+    }
+
+    #[test]
+    fn capturing_promotion() {
+        let mv = played(include_str!("boards/promotion.fen"), "e7", "f8", Some('q'));
+        assert!(mv.is_promotion());
+        assert!(mv.is_capture());
+        assert_eq!(mv.captured, Some(PieceType::Rook));
+        assert_eq!(mv.promotion, Some(PieceType::Queen));
+    }
 }
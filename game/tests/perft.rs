@@ -0,0 +1,71 @@
+macro_rules! perft {
+    ($name:ident, $position: ident, $depth: expr, $nodes: expr) => {
+        #[test]
+        fn $name() {
+            const FEN_POSITION: &str =
+                include_str!(concat!("boards/", stringify!($position), ".fen"));
+            let board = Board::from_fen(FEN_POSITION).unwrap();
+            assert_eq!(board.perft($depth), $nodes);
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod perft {
+    use game::Board;
+
+    // Reference positions & node counts from https://www.chessprogramming.org/Perft_Results
+    perft!(starting_depth_1, starting, 1, 20);
+    perft!(starting_depth_2, starting, 2, 400);
+    perft!(starting_depth_3, starting, 3, 8_902);
+    perft!(kiwipete_depth_1, kiwipete, 1, 48);
+    perft!(kiwipete_depth_2, kiwipete, 2, 2_039);
+    perft!(kiwipete_depth_3, kiwipete, 3, 97_862);
+    perft!(position_3_depth_4, perft_position_3, 4, 43_238);
+    perft!(position_4_depth_3, perft_position_4, 3, 9_467);
+    perft!(mirrored_4_depth_3, perft_position_4_mirrored, 3, 9_467);
+    perft!(position_5_depth_3, perft_position_5, 3, 62_379);
+    perft!(position_6_depth_3, perft_position_6, 3, 89_890);
+
+    // En passant, promotion & castling edge cases from the TalkChess perft suite. The suite gives
+    // node counts at depth 4 to 6, which are too slow for a debug build, so these are checked at
+    // a lower depth. The published counts are given above each test.
+
+    // 1_440_467 at depth 6.
+    perft!(ep_check_evasion, en_passant_check_evasion, 4, 13_931);
+    // 1_134_888 at depth 6.
+    perft!(ep_discovered_check, en_passant_discovered_check, 4, 10_138);
+    // 1_015_133 at depth 6.
+    perft!(ep_pinned, en_passant_pinned, 4, 10_276);
+    // 3_821_001 at depth 6.
+    perft!(promote_out_of_check, promote_out_of_check, 4, 19_174);
+    // 217_342 at depth 6.
+    perft!(promote_to_give_check, promote_to_give_check, 4, 2_661);
+    // 92_683 at depth 6.
+    perft!(underpromote, underpromote_to_avoid_stalemate, 4, 1_329);
+    // 1_720_476 at depth 4.
+    perft!(castling_prevented, castling_prevented, 3, 50_509);
+    // 1_274_206 at depth 4.
+    perft!(castling_rights_lost, castling_rights_lost, 3, 27_826);
+
+    // Chess960 positions from https://www.chessprogramming.org/Chess960_Perft_Results, with the
+    // depth 4 counts given above each test.
+
+    // 326_672 at depth 4.
+    perft!(chess960_position_1, chess960_position_1, 3, 12_189);
+    // 667_366 at depth 4.
+    perft!(chess960_position_2, chess960_position_2, 3, 18_002);
+    // 273_318 at depth 4.
+    perft!(chess960_position_3, chess960_position_3, 3, 10_471);
+    // 382_958 at depth 4.
+    perft!(chess960_position_4, chess960_position_4, 3, 13_440);
+
+    #[test]
+    fn divide_sums_to_perft() {
+        let board = Board::from_fen(include_str!("boards/kiwipete.fen")).unwrap();
+        let divide = board.perft_divide(2);
+        assert_eq!(divide.len(), 48);
+        assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2_039);
+    }
+}
@@ -0,0 +1,119 @@
+mod pgn {
+    use game::{read_pgn, Board, Game, GameResult, Move, PgnError, PgnGame};
+
+    const OPERA: &str = include_str!("games/opera.pgn");
+    const ANNOTATED: &str = include_str!("games/annotated.pgn");
+    const ILLEGAL: &str = include_str!("games/illegal.pgn");
+
+    fn sans(game: &PgnGame) -> Vec<String> {
+        game.game
+            .positions()
+            .zip(game.game.moves())
+            .map(|(position, mv)| position.to_san(mv).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn reads_game() {
+        let games = read_pgn(OPERA).unwrap();
+        assert_eq!(games.len(), 1);
+
+        let opera = &games[0];
+        assert_eq!(opera.tag("White"), Some("Paul Morphy"));
+        assert_eq!(opera.tag("ECO"), Some("C41"));
+        assert_eq!(opera.result, GameResult::WhiteWins);
+        assert_eq!(opera.game.moves().count(), 33);
+        assert_eq!(sans(opera).last().unwrap(), "Rd8#");
+        assert_eq!(GameResult::from(opera.game.status()), GameResult::WhiteWins);
+    }
+
+    #[test]
+    fn reads_multiple_games_with_comments_nags_and_variations() {
+        let games = read_pgn(ANNOTATED).unwrap();
+        assert_eq!(games.len(), 2);
+
+        let fools_mate = &games[0];
+        assert_eq!(fools_mate.tag("Annotator"), Some("Someone \"quoted\""));
+        assert_eq!(fools_mate.result, GameResult::BlackWins);
+        assert_eq!(sans(fools_mate), vec!["f3", "e5", "g4", "Qh4#"]);
+
+        let unfinished = &games[1];
+        assert_eq!(unfinished.tag("Event"), Some("Unfinished"));
+        assert_eq!(unfinished.result, GameResult::Unknown);
+        assert_eq!(sans(unfinished), vec!["Kd7", "O-O"]);
+    }
+
+    #[test]
+    fn reports_illegal_move_position() {
+        match read_pgn(ILLEGAL).err().unwrap() {
+            PgnError::IllegalMove {
+                line, column, san, ..
+            } => {
+                assert_eq!((line, column), (5, 8));
+                assert_eq!(san, "Nf3");
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn reports_unterminated_comment() {
+        match read_pgn("1. e4 {oops").err().unwrap() {
+            PgnError::UnterminatedComment { line, column } => {
+                assert_eq!((line, column), (1, 7))
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn writes_game() {
+        let mut game = Game::new();
+        for (src, dst) in &[("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")] {
+            game.play(&Move::from_notation(src, dst, None).unwrap())
+                .unwrap();
+        }
+
+        let mut pgn_game = PgnGame::new(game);
+        pgn_game.set_tag("White", "Fool");
+        pgn_game.set_tag("Annotator", "Me");
+
+        assert_eq!(
+            pgn_game.to_pgn(),
+            "[Event \"?\"]\n\
+             [Site \"?\"]\n\
+             [Date \"????.??.??\"]\n\
+             [Round \"?\"]\n\
+             [White \"Fool\"]\n\
+             [Black \"?\"]\n\
+             [Result \"0-1\"]\n\
+             [Annotator \"Me\"]\n\
+             \n\
+             1. f3 e5 2. g4 Qh4# 0-1\n"
+        );
+    }
+
+    #[test]
+    fn writes_setup_position() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R b K - 0 10").unwrap();
+        let mut game = Game::from_position(board);
+        game.play(&Move::from_notation("e8", "d7", None).unwrap())
+            .unwrap();
+
+        let pgn = PgnGame::new(game).to_pgn();
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/4K2R b K - 0 10\"]\n"));
+        assert!(pgn.ends_with("\n10... Kd7 *\n"));
+    }
+
+    #[test]
+    fn round_trip_wraps_lines() {
+        let opera = read_pgn(OPERA).unwrap().remove(0);
+        let pgn = opera.to_pgn();
+        assert!(pgn.lines().all(|line| line.len() <= 80));
+
+        let read_back = read_pgn(&pgn).unwrap().remove(0);
+        assert_eq!(read_back.tags, opera.tags);
+        assert_eq!(read_back.result, opera.result);
+        assert_eq!(sans(&read_back), sans(&opera));
+    }
+}
@@ -0,0 +1,127 @@
+macro_rules! san_round_trip {
+    ($name:ident, $position: ident) => {
+        #[test]
+        fn $name() {
+            const FEN_POSITION: &str =
+                include_str!(concat!("boards/", stringify!($position), ".fen"));
+            let board = Board::from_fen(FEN_POSITION).unwrap();
+            for mv in board.legal_moves() {
+                let san = board.to_san(&mv).unwrap();
+                assert_eq!(board.parse_san(&san).unwrap(), mv, "{}", san);
+            }
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod san {
+    use game::{Board, Move, MoveKind, PieceType, SanError};
+
+    fn san_of(fen: &str, src: &str, dst: &str, promotion: Option<char>) -> String {
+        let board = Board::from_fen(fen).unwrap();
+        let mv = Move::from_notation(src, dst, promotion).unwrap();
+        board.to_san(&mv).unwrap()
+    }
+
+    fn parse(fen: &str, san: &str) -> Result<Move, SanError> {
+        Board::from_fen(fen).unwrap().parse_san(san)
+    }
+
+    const STARTING: &str = include_str!("boards/starting.fen");
+    const CASTLING: &str = include_str!("boards/castling.fen");
+    const PROMOTION: &str = include_str!("boards/promotion.fen");
+
+    const KNIGHTS_SAME_RANK: &str = "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1";
+    const ROOKS_SAME_FILE: &str = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    const THREE_QUEENS: &str = "4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1";
+    const BEFORE_FOOLS_MATE: &str = "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2";
+
+    san_round_trip!(starting_round_trip, starting);
+    san_round_trip!(castling_round_trip, castling);
+    san_round_trip!(promotion_round_trip, promotion);
+    san_round_trip!(en_passant_round_trip, en_passant);
+    san_round_trip!(pinned_pawn_round_trip, pinned_pawn);
+
+    #[test]
+    fn formats_simple_moves() {
+        assert_eq!(san_of(STARTING, "e2", "e4", None), "e4");
+        assert_eq!(san_of(STARTING, "g1", "f3", None), "Nf3");
+        assert_eq!(
+            san_of(include_str!("boards/pinned_pawn.fen"), "d2", "c3", None),
+            "dxc3"
+        );
+        assert_eq!(
+            san_of(include_str!("boards/en_passant.fen"), "e4", "f3", None),
+            "exf3"
+        );
+    }
+
+    #[test]
+    fn formats_castling() {
+        assert_eq!(san_of(CASTLING, "e1", "g1", None), "O-O");
+        assert_eq!(san_of(CASTLING, "e1", "c1", None), "O-O-O");
+    }
+
+    #[test]
+    fn formats_promotion_with_check() {
+        assert_eq!(san_of(PROMOTION, "e7", "e8", Some('q')), "e8=Q+");
+        assert_eq!(san_of(PROMOTION, "e7", "f8", Some('n')), "exf8=N");
+    }
+
+    #[test]
+    fn formats_mate() {
+        assert_eq!(san_of(BEFORE_FOOLS_MATE, "d8", "h4", None), "Qh4#");
+    }
+
+    #[test]
+    fn formats_disambiguation() {
+        assert_eq!(san_of(KNIGHTS_SAME_RANK, "b1", "d2", None), "Nbd2");
+        assert_eq!(san_of(ROOKS_SAME_FILE, "a1", "a3", None), "R1a3");
+        assert_eq!(san_of(ROOKS_SAME_FILE, "a5", "a3", None), "R5a3");
+        assert_eq!(san_of(THREE_QUEENS, "a1", "b2", None), "Qa1b2");
+        assert_eq!(san_of(THREE_QUEENS, "c1", "b2", None), "Qcb2");
+    }
+
+    #[test]
+    fn parses_moves() {
+        let mv = parse(STARTING, "Nf3").unwrap();
+        assert_eq!(mv, Move::from_notation("g1", "f3", None).unwrap());
+        assert_eq!(mv.piece, Some(PieceType::Knight));
+
+        let mv = parse(PROMOTION, "e8=Q+").unwrap();
+        assert_eq!(mv, Move::from_notation("e7", "e8", Some('q')).unwrap());
+
+        let mv = parse(PROMOTION, "exf8N").unwrap();
+        assert_eq!(mv, Move::from_notation("e7", "f8", Some('n')).unwrap());
+
+        assert_eq!(
+            parse(CASTLING, "O-O").unwrap().kind,
+            MoveKind::KingsideCastle
+        );
+        assert_eq!(
+            parse(CASTLING, "0-0-0").unwrap().kind,
+            MoveKind::QueensideCastle
+        );
+
+        let mv = parse(THREE_QUEENS, "Qa1b2").unwrap();
+        assert_eq!(mv, Move::from_notation("a1", "b2", None).unwrap());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse(STARTING, "e5").err(), Some(SanError::NoMatchingMove));
+        assert_eq!(parse(STARTING, "O-O").err(), Some(SanError::NoMatchingMove));
+        assert_eq!(parse(PROMOTION, "e8").err(), Some(SanError::NoMatchingMove));
+        assert_eq!(
+            parse(KNIGHTS_SAME_RANK, "Nd2").err(),
+            Some(SanError::AmbiguousMove)
+        );
+        assert_eq!(
+            parse(THREE_QUEENS, "Qab2").err(),
+            Some(SanError::AmbiguousMove)
+        );
+        assert_eq!(parse(STARTING, "").err(), Some(SanError::InvalidNotation));
+        assert!(parse(STARTING, "Nz3").is_err());
+    }
+}
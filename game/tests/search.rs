@@ -0,0 +1,326 @@
+macro_rules! mate {
+    ($name:ident, $position: ident, $moves: expr) => {
+        #[test]
+        fn $name() {
+            let board = Board::from_fen(include_str!(concat!(
+                "boards/",
+                stringify!($position),
+                ".fen"
+            )))
+            .unwrap();
+            let result = Search::new().run(&board, &SearchLimits::depth(2 * $moves));
+            assert_eq!(result.score, Score::Mate($moves));
+            assert_eq!(result.best_move, result.pv.first().cloned());
+            assert_eq!(result.pv.len(), 2 * $moves - 1);
+            assert!(matches!(
+                play_pv(&board, &result.pv).status(),
+                GameStatus::Checkmate { .. }
+            ));
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod search {
+    use std::sync::atomic::Ordering;
+    use std::thread;
+    use std::time::Duration;
+
+    use game::{
+        board_with_setup, Board, Game, GameStatus, Move, Score, Search, SearchLimits, SearchOptions,
+    };
+
+    /// Plays the principal variation, checking that its moves are legal.
+    fn play_pv(board: &Board, pv: &[Move]) -> Board {
+        let mut board = board.clone();
+        for mv in pv {
+            board = board.move_piece(mv).unwrap().0;
+        }
+        board
+    }
+
+    mate!(mate_in_one, mate_in_one, 1);
+    mate!(mate_in_two, mate_in_two, 2);
+
+    #[test]
+    fn takes_a_hanging_queen() {
+        let board = Board::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let result = Search::new().run(&board, &SearchLimits::depth(3));
+        assert_eq!(result.best_move.unwrap().to_string(), "d2d5");
+        assert!(matches!(result.score, Score::Centipawns(cp) if cp > 300));
+    }
+
+    #[test]
+    fn looks_past_the_horizon() {
+        // At depth 1, taking the pawn looks like it wins one, until the pawn recaptures.
+        let board = Board::from_fen("4k3/8/3p4/4p3/8/8/4Q3/4K3 w - - 0 1").unwrap();
+        let result = Search::new().run(&board, &SearchLimits::depth(1));
+        assert_ne!(result.best_move.unwrap().to_string(), "e2e5");
+        assert!(matches!(result.score, Score::Centipawns(cp) if cp > 500));
+    }
+
+    #[test]
+    fn sees_mate_coming() {
+        let board = Board::from_fen("r5k1/5ppp/8/8/8/5p1p/5P1P/6K1 w - - 0 1").unwrap();
+        let result = Search::new().run(&board, &SearchLimits::depth(3));
+        assert_eq!(result.score, Score::Mate(-1));
+    }
+
+    #[test]
+    fn game_over() {
+        let board = Board::from_fen(include_str!("boards/checkmate.fen")).unwrap();
+        let result = Search::new().run(&board, &SearchLimits::depth(3));
+        assert!(result.best_move.is_none());
+        assert_eq!(result.score, Score::Mate(0));
+
+        let board = Board::from_fen(include_str!("boards/stalemate.fen")).unwrap();
+        let result = Search::new().run(&board, &SearchLimits::depth(3));
+        assert!(result.best_move.is_none());
+        assert_eq!(result.score, Score::Centipawns(0));
+    }
+
+    #[test]
+    fn draws_score_zero() {
+        let board =
+            Board::from_fen(include_str!("boards/king_and_knight_against_king.fen")).unwrap();
+        let result = Search::new().run(&board, &SearchLimits::depth(3));
+        assert_eq!(result.score, Score::Centipawns(0));
+    }
+
+    #[test]
+    fn repetitions_of_the_game_are_draws() {
+        // White is a queen down, but can play the knight back to a position already played.
+        let board = Board::from_fen("q3k3/8/8/8/8/8/8/4K1N1 w - - 0 1").unwrap();
+        let mut game = Game::from_position(board);
+        for san in &["Nf3", "Kd8", "Ng1", "Ke8"] {
+            let mv = game.position().parse_san(san).unwrap();
+            game.play(&mv).unwrap();
+        }
+
+        let result = Search::new().run_game(&game, &SearchLimits::depth(2));
+        assert_eq!(result.score, Score::Centipawns(0));
+        assert_eq!(result.best_move.unwrap().to_string(), "g1f3");
+
+        let result = Search::new().run(game.position(), &SearchLimits::depth(2));
+        assert!(matches!(result.score, Score::Centipawns(cp) if cp < -500));
+    }
+
+    #[test]
+    fn depth_limit() {
+        let result = Search::new().run(&board_with_setup(), &SearchLimits::depth(3));
+        assert_eq!(result.depth, 3);
+        assert_eq!(result.pv.len(), 3);
+        assert_eq!(result.best_move, result.pv.first().cloned());
+        play_pv(&board_with_setup(), &result.pv);
+    }
+
+    #[test]
+    fn node_limit() {
+        let result = Search::new().run(&board_with_setup(), &SearchLimits::nodes(2_000));
+        assert!(result.nodes <= 2_000);
+        assert!(result.depth >= 1);
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn time_limit() {
+        let limit = Duration::from_millis(200);
+        let result = Search::new().run(&board_with_setup(), &SearchLimits::time(limit));
+        assert!(result.time < limit + Duration::from_millis(200));
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn stop_flag() {
+        let mut search = Search::new();
+        let stop = search.stop_flag();
+        let stopper = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            stop.store(true, Ordering::Relaxed);
+        });
+
+        let result = search.run(&board_with_setup(), &SearchLimits::default());
+        stopper.join().unwrap();
+        assert!(result.best_move.is_some());
+        assert!(!search.stop_flag().load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn progress() {
+        let game = Game::new();
+        let mut iterations = Vec::new();
+        let result = Search::new().run_game_with_progress(&game, &SearchLimits::depth(4), |r| {
+            iterations.push(r.clone())
+        });
+
+        let depths: Vec<_> = iterations.iter().map(|r| r.depth).collect();
+        assert_eq!(depths, vec![1, 2, 3, 4]);
+        assert!(iterations.windows(2).all(|w| w[0].nodes <= w[1].nodes));
+
+        let last = iterations.last().unwrap();
+        assert_eq!(last.pv, result.pv);
+        assert_eq!(last.score, result.score);
+        assert_eq!(last.nodes, result.nodes);
+    }
+
+    #[test]
+    fn root_moves() {
+        let board = Board::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let limits = SearchLimits {
+            depth: Some(3),
+            root_moves: vec![Move::from_uci("e1f1").unwrap().unwrap()],
+            ..Default::default()
+        };
+        let result = Search::new().run(&board, &limits);
+        assert_eq!(result.best_move.unwrap().to_string(), "e1f1");
+
+        // Moves that aren't legal leave the choice open.
+        let limits = SearchLimits {
+            depth: Some(3),
+            root_moves: vec![Move::from_uci("e1e3").unwrap().unwrap()],
+            ..Default::default()
+        };
+        let result = Search::new().run(&board, &limits);
+        assert_eq!(result.best_move.unwrap().to_string(), "d2d5");
+    }
+
+    #[test]
+    fn ponder_flag() {
+        let mut search = Search::new();
+        let ponder = search.ponder_flag();
+        ponder.store(true, Ordering::Relaxed);
+        let ponderhit = {
+            let ponder = ponder.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(300));
+                ponder.store(false, Ordering::Relaxed);
+            })
+        };
+
+        // The time limit only counts once the opponent plays the expected move.
+        let limits = SearchLimits::time(Duration::from_millis(50));
+        let result = search.run(&board_with_setup(), &limits);
+        ponderhit.join().unwrap();
+        assert!(result.time >= Duration::from_millis(300));
+        assert!(result.time < Duration::from_millis(300 + 250));
+        assert!(!ponder.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn transposition_table() {
+        let board = board_with_setup();
+        let mut search = Search::new();
+        let first = search.run(&board, &SearchLimits::depth(4));
+        assert!(first.table_stats.hits > 0);
+        assert!(first.table_stats.stores > 0);
+        assert!(first.table_stats.hit_rate() > 0.0 && first.table_stats.hit_rate() < 1.0);
+
+        // The second search starts from what the first one found.
+        let second = search.run(&board, &SearchLimits::depth(4));
+        assert!(second.nodes < first.nodes);
+        assert_eq!(second.score, first.score);
+
+        search.clear_table();
+        let cleared = search.run(&board, &SearchLimits::depth(4));
+        assert!(cleared.nodes > second.nodes);
+    }
+
+    #[test]
+    fn move_ordering() {
+        let board = Board::from_fen(include_str!("boards/kiwipete.fen")).unwrap();
+        let result = Search::new().run(&board, &SearchLimits::depth(4));
+        let stats = result.ordering_stats;
+        assert!(stats.cutoffs > 0);
+        assert!(stats.first_move_cutoffs <= stats.cutoffs);
+        assert!(stats.first_move_cutoff_rate() > 0.5);
+    }
+
+    #[test]
+    fn mates_through_the_transposition_table() {
+        let board = Board::from_fen(include_str!("boards/mate_in_two.fen")).unwrap();
+        let mut search = Search::with_table_size(1);
+        for _ in 0..2 {
+            let result = search.run(&board, &SearchLimits::depth(4));
+            assert_eq!(result.score, Score::Mate(2));
+            assert_eq!(result.pv.len(), 3);
+        }
+    }
+
+    #[test]
+    fn selective_search() {
+        let board = Board::from_fen(include_str!("boards/kiwipete.fen")).unwrap();
+        let mut search = Search::new();
+        search.set_options(SearchOptions::none());
+        let full = search.run(&board, &SearchLimits::depth(4));
+
+        let mut search = Search::new();
+        assert_eq!(search.options(), SearchOptions::default());
+        let selective = search.run(&board, &SearchLimits::depth(4));
+        assert!(selective.nodes < full.nodes);
+        play_pv(&board, &selective.pv);
+    }
+
+    #[test]
+    fn options_can_be_turned_off_one_at_a_time() {
+        let board = Board::from_fen(include_str!("boards/mate_in_two.fen")).unwrap();
+        let all = SearchOptions::default();
+        let options = [
+            SearchOptions {
+                null_move_pruning: false,
+                ..all
+            },
+            SearchOptions {
+                late_move_reductions: false,
+                ..all
+            },
+            SearchOptions {
+                futility_pruning: false,
+                ..all
+            },
+            SearchOptions {
+                reverse_futility_pruning: false,
+                ..all
+            },
+            SearchOptions {
+                check_extensions: false,
+                ..all
+            },
+            SearchOptions {
+                aspiration_windows: false,
+                ..all
+            },
+            SearchOptions::none(),
+        ];
+        for options in options.iter() {
+            let mut search = Search::new();
+            search.set_options(*options);
+            let result = search.run(&board, &SearchLimits::depth(4));
+            assert_eq!(result.score, Score::Mate(2));
+        }
+    }
+
+    #[test]
+    fn no_null_moves_in_pawn_endings() {
+        let board = Board::from_fen("8/5k2/3p4/1p1Pp2p/pP2Pp1P/P4P1K/8/8 b - - 0 1").unwrap();
+        let mut search = Search::new();
+        let with_null_moves = search.run(&board, &SearchLimits::depth(6));
+
+        let mut search = Search::new();
+        search.set_options(SearchOptions {
+            null_move_pruning: false,
+            ..SearchOptions::default()
+        });
+        let without = search.run(&board, &SearchLimits::depth(6));
+        assert_eq!(with_null_moves.nodes, without.nodes);
+        assert_eq!(with_null_moves.score, without.score);
+    }
+
+    #[test]
+    fn score_display() {
+        assert_eq!(Score::Centipawns(35).to_string(), "+0.35");
+        assert_eq!(Score::Centipawns(-120).to_string(), "-1.20");
+        assert_eq!(Score::Mate(3).to_string(), "#3");
+        assert_eq!(Score::Mate(-2).to_string(), "#-2");
+    }
+}
@@ -0,0 +1,74 @@
+macro_rules! see {
+    ($name:ident, $fen: expr, $uci: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let board = Board::from_fen($fen).unwrap();
+            let mv = Move::from_uci($uci).unwrap().unwrap();
+            assert_eq!(board.see(&mv), $expected);
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod see {
+    use game::{Board, Move};
+
+    see!(
+        undefended_piece,
+        "4k3/8/8/3n4/4P3/8/8/4K3 w - - 0 1",
+        "e4d5",
+        300
+    );
+    see!(
+        defended_pawn,
+        "4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1",
+        "d1d5",
+        -800
+    );
+    see!(
+        rook_behind_rook,
+        "3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1",
+        "d2d5",
+        100
+    );
+    see!(
+        bishop_behind_queen,
+        "4r1k1/8/4n3/8/2Q5/1B6/8/4K3 w - - 0 1",
+        "c4e6",
+        -100
+    );
+    see!(
+        defended_by_the_king,
+        "8/8/4k3/3p4/8/8/8/3RK3 w - - 0 1",
+        "d1d5",
+        -400
+    );
+    see!(
+        king_cannot_recapture,
+        "8/8/4k3/3p4/2P5/8/8/3RK3 w - - 0 1",
+        "d1d5",
+        100
+    );
+    see!(en_passant, "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6", 100);
+    see!(
+        quiet_move_to_attacked_square,
+        "4k3/8/8/2p5/8/5N2/8/4K3 w - - 0 1",
+        "f3d4",
+        -300
+    );
+    see!(
+        quiet_move_to_safe_square,
+        "4k3/8/8/2p5/8/5N2/8/4K3 w - - 0 1",
+        "f3e5",
+        0
+    );
+    see!(promotion, "4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q", 800);
+    see!(
+        promotion_recaptured,
+        "1rk5/P7/8/8/8/8/8/4K3 w - - 0 1",
+        "a7b8q",
+        400
+    );
+    see!(castling, include_str!("boards/castling.fen"), "e1g1", 0);
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "serde")]
+
+mod serialization {
+    use game::{board_with_setup, Board, Color, MoveError, Piece, PieceType, SanError, Square};
+    use serde_json::json;
+
+    #[test]
+    fn squares_use_notation() {
+        let square = Square::from_notation("e4").unwrap();
+        assert_eq!(serde_json::to_value(square).unwrap(), json!("e4"));
+        assert_eq!(
+            serde_json::from_value::<Square>(json!("e4")).unwrap(),
+            square
+        );
+        assert!(serde_json::from_value::<Square>(json!("e9")).is_err());
+        assert!(serde_json::from_value::<Square>(json!(12)).is_err());
+    }
+
+    #[test]
+    fn pieces() {
+        let piece = Piece::new(PieceType::Knight, Color::White);
+        let value = json!({"piece_type": "knight", "color": "white"});
+        assert_eq!(serde_json::to_value(piece).unwrap(), value);
+        assert_eq!(serde_json::from_value::<Piece>(value).unwrap(), piece);
+
+        assert_eq!(serde_json::to_value(Color::Black).unwrap(), json!("black"));
+        assert_eq!(
+            serde_json::to_value(PieceType::Queen).unwrap(),
+            json!("queen")
+        );
+    }
+
+    #[test]
+    fn boards_use_fen() {
+        let board = board_with_setup();
+        let value = serde_json::to_value(&board).unwrap();
+        assert_eq!(
+            value,
+            json!("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+        );
+
+        let loaded: Board = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded.to_fen(), board.to_fen());
+        assert!(serde_json::from_value::<Board>(json!("not a position")).is_err());
+    }
+
+    #[test]
+    fn move_errors_have_codes() {
+        let err = board_with_setup()
+            .move_notation("e2", "e5", None)
+            .unwrap_err();
+        assert_eq!(err, MoveError::InvalidMove);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({"code": "invalid_move", "message": "This piece doesn't move like that!"})
+        );
+
+        let err = board_with_setup()
+            .move_notation("e2", "e9", None)
+            .unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&err).unwrap()["code"],
+            "invalid_square"
+        );
+    }
+
+    #[test]
+    fn san_errors_have_codes() {
+        let err = board_with_setup().parse_san("Nf6").unwrap_err();
+        assert_eq!(err, SanError::NoMatchingMove);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({"code": "no_matching_move", "message": "No legal move matches this notation."})
+        );
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+        let err = board.parse_san("Rd1").unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&err).unwrap()["code"],
+            "ambiguous_move"
+        );
+
+        let err = board_with_setup().parse_san("").unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&err).unwrap()["code"],
+            "invalid_notation"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+mod squares {
+    use std::convert::TryFrom;
+
+    use game::{Column, NotationError, Square};
+
+    fn sq(notation: &str) -> Square {
+        Square::from_notation(notation).unwrap()
+    }
+
+    fn notations(squares: impl Iterator<Item = Square>) -> Vec<String> {
+        squares.map(String::from).collect()
+    }
+
+    #[test]
+    fn notation_round_trip() {
+        for square in Square::ALL.iter() {
+            let notation = square.to_string();
+            assert_eq!(sq(&notation), *square);
+            assert_eq!(notation.parse::<Square>().unwrap(), *square);
+            assert_eq!(String::from(*square), notation);
+        }
+
+        assert_eq!(sq("E4"), sq("e4"));
+        assert_eq!(sq("e4").col(), Column::E);
+        assert_eq!(sq("e4").row(), 3);
+        assert_eq!(Square::new(Column::E, 3), sq("e4"));
+    }
+
+    #[test]
+    fn invalid_notation() {
+        assert_eq!(Square::from_notation("e9"), Err(NotationError::RowTooBig));
+        assert_eq!(Square::from_notation("e0"), Err(NotationError::RowTooBig));
+        assert_eq!(
+            Square::from_notation("i1"),
+            Err(NotationError::InvalidColumn)
+        );
+        assert_eq!(Square::from_notation("e"), Err(NotationError::ParseError));
+        assert!(Square::try_new(Column::A, 8).is_none());
+        assert!(Square::try_from(64).is_err());
+    }
+
+    #[test]
+    fn indices() {
+        assert_eq!(Square::ALL.len(), 64);
+        for (index, square) in Square::ALL.iter().enumerate() {
+            assert_eq!(square.index(), index);
+            assert_eq!(Square::try_from(index).unwrap(), *square);
+        }
+        assert_eq!(sq("a1").index(), 0);
+        assert_eq!(sq("h1").index(), 7);
+        assert_eq!(sq("h8").index(), 63);
+    }
+
+    #[test]
+    fn offsets() {
+        let e4 = sq("e4");
+        assert_eq!(e4.relative(1, 2), Some(sq("f6")));
+        assert_eq!(e4.relative(-4, -3), Some(sq("a1")));
+        assert_eq!(e4.relative(4, 0), None);
+        assert_eq!(e4.relative(0, -4), None);
+        assert_eq!(sq("h4").next_col(), None);
+        assert_eq!(sq("a4").prev_col(), None);
+        assert_eq!(e4.row_move(4), Some(sq("e8")));
+        assert_eq!(e4.col_move(-2), Some(sq("c4")));
+    }
+
+    #[test]
+    fn lines() {
+        let e4 = sq("e4");
+        assert_eq!(
+            notations(e4.file()),
+            ["e1", "e2", "e3", "e4", "e5", "e6", "e7", "e8"]
+        );
+        assert_eq!(
+            notations(e4.rank()),
+            ["a4", "b4", "c4", "d4", "e4", "f4", "g4", "h4"]
+        );
+        assert_eq!(
+            notations(e4.diagonal()),
+            ["b1", "c2", "d3", "e4", "f5", "g6", "h7"]
+        );
+        assert_eq!(
+            notations(e4.anti_diagonal()),
+            ["h1", "g2", "f3", "e4", "d5", "c6", "b7", "a8"]
+        );
+        assert_eq!(notations(sq("h1").diagonal()), ["h1"]);
+        assert_eq!(notations(sq("a1").anti_diagonal()), ["a1"]);
+    }
+
+    #[test]
+    fn distances() {
+        let (a1, e4) = (sq("a1"), sq("e4"));
+        assert_eq!(a1.file_distance(e4), 4);
+        assert_eq!(a1.rank_distance(e4), 3);
+        assert_eq!(a1.distance(e4), 4);
+        assert_eq!(e4.manhattan_distance(a1), 7);
+        assert_eq!(e4.distance(e4), 0);
+    }
+}
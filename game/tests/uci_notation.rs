@@ -0,0 +1,85 @@
+macro_rules! uci_round_trip {
+    ($name:ident, $position: ident) => {
+        #[test]
+        fn $name() {
+            const FEN_POSITION: &str =
+                include_str!(concat!("boards/", stringify!($position), ".fen"));
+            let board = Board::from_fen(FEN_POSITION).unwrap();
+            for mv in board.legal_moves() {
+                let uci = mv.to_uci();
+                let parsed = Move::from_uci(&uci).unwrap().unwrap();
+                assert_eq!(parsed, mv, "{}", uci);
+
+                let (_, played) = board.move_piece(&parsed).unwrap();
+                assert_eq!(played.kind, mv.kind, "{}", uci);
+            }
+        }
+    };
+}
+
+// BEGIN TESTS.
+
+mod uci_notation {
+    use game::{Board, Move, MoveKind, NotationError, PieceType, NULL_MOVE_UCI};
+
+    uci_round_trip!(starting_round_trip, starting);
+    uci_round_trip!(castling_round_trip, castling);
+    uci_round_trip!(promotion_round_trip, promotion);
+    uci_round_trip!(en_passant_round_trip, en_passant);
+    uci_round_trip!(pinned_pawn_round_trip, pinned_pawn);
+    uci_round_trip!(knight_boxed_round_trip, knight_boxed);
+
+    #[test]
+    fn formats_moves() {
+        let board = Board::from_fen(include_str!("boards/promotion.fen")).unwrap();
+        let (_, mv) = board.move_notation("e7", "f8", Some('q')).unwrap();
+        assert_eq!(mv.to_uci(), "e7f8q");
+        assert_eq!(mv.to_string(), "e7f8q");
+
+        let board = Board::from_fen(include_str!("boards/castling.fen")).unwrap();
+        let (_, mv) = board.move_notation("e1", "g1", None).unwrap();
+        assert_eq!(mv.to_uci(), "e1g1");
+    }
+
+    #[test]
+    fn parses_moves() {
+        let mv = Move::from_uci("e7e8n").unwrap().unwrap();
+        assert_eq!(mv, Move::from_notation("e7", "e8", Some('n')).unwrap());
+        assert_eq!(mv.promotion, Some(PieceType::Knight));
+
+        let board = Board::from_fen(include_str!("boards/castling.fen")).unwrap();
+        let castle = Move::from_uci("e1c1").unwrap().unwrap();
+        let (_, played) = board.move_piece(&castle).unwrap();
+        assert_eq!(played.kind, MoveKind::QueensideCastle);
+    }
+
+    #[test]
+    fn parses_null_move() {
+        assert_eq!(Move::from_uci(NULL_MOVE_UCI).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Move::from_uci("e2e").err(),
+            Some(NotationError::InvalidMoveLength)
+        );
+        assert_eq!(
+            Move::from_uci("e2e4qq").err(),
+            Some(NotationError::InvalidMoveLength)
+        );
+        assert_eq!(
+            Move::from_uci("e7e8k").err(),
+            Some(NotationError::InvalidPromotionPiece)
+        );
+        assert_eq!(
+            Move::from_uci("e7e8x").err(),
+            Some(NotationError::InvalidPromotionPiece)
+        );
+        assert_eq!(
+            Move::from_uci("z2e4").err(),
+            Some(NotationError::InvalidColumn)
+        );
+        assert_eq!(Move::from_uci("e9e4").err(), Some(NotationError::RowTooBig));
+    }
+}
@@ -8,9 +8,49 @@ export class API {
         return response.data.position_fen;
     }
 
-    async move(s, d) {
-        let response = await axios.post(`${HOST}/move`, { src: s, dst: d });
+    async setPosition(fen) {
+        let response = await axios.post(`${HOST}/position`, { fen: fen });
+        return {
+            position: response.data.position_fen,
+            error: response.data.error
+        }
+    }
+
+    async move(s, d, promotion = null) {
+        let response = await axios.post(`${HOST}/move`, { src: s, dst: d, promotion: promotion });
         console.log(response);
+        return {
+            position: response.data.position_fen,
+            san: response.data.san,
+            status: response.data.status,
+            error: response.data.error,
+            moveError: response.data.move_error
+        }
+    }
+
+    async moveSan(san) {
+        let response = await axios.post(`${HOST}/move`, { san: san });
+        return {
+            position: response.data.position_fen,
+            san: response.data.san,
+            status: response.data.status,
+            error: response.data.error,
+            moveError: response.data.move_error
+        }
+    }
+
+    async engineMove(timeMs = null) {
+        let response = await axios.post(`${HOST}/engine_move`, { time_ms: timeMs });
+        return {
+            position: response.data.position_fen,
+            san: response.data.san,
+            status: response.data.status,
+            error: response.data.error
+        }
+    }
+
+    async undo() {
+        let response = await axios.post(`${HOST}/undo`);
         return {
             position: response.data.position_fen,
             error: response.data.error
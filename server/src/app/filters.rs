@@ -3,9 +3,11 @@ use warp::Filter;
 
 use super::{handlers, ServerRC};
 
+const ENGINE_MOVE_ROUTE_PATH: &str = "engine_move";
 const HELLO_ROUTE_PATH: &str = "hello";
 const MOVE_ROUTE_PATH: &str = "move";
 const POSITION_ROUTE_PATH: &str = "position";
+const UNDO_ROUTE_PATH: &str = "undo";
 
 fn with_server(
     srv: ServerRC,
@@ -30,7 +32,10 @@ pub fn all(
 
     hello(srv.clone())
         .or(get_position(srv.clone()))
-        .or(do_move(srv))
+        .or(set_position(srv.clone()))
+        .or(do_move(srv.clone()))
+        .or(engine_move(srv.clone()))
+        .or(undo(srv))
         .with(cors)
 }
 
@@ -53,6 +58,25 @@ pub fn do_move(
         .and_then(handlers::do_move)
 }
 
+pub fn engine_move(
+    srv: ServerRC,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::path(ENGINE_MOVE_ROUTE_PATH))
+        .and(json_body::<handlers::EngineMovePayload>())
+        .and(with_server(srv))
+        .and_then(handlers::engine_move)
+}
+
+pub fn undo(
+    srv: ServerRC,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::path(UNDO_ROUTE_PATH))
+        .and(with_server(srv))
+        .and_then(handlers::undo)
+}
+
 pub fn get_position(
     srv: ServerRC,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
@@ -61,3 +85,13 @@ pub fn get_position(
         .and(with_server(srv))
         .and_then(handlers::get_position)
 }
+
+pub fn set_position(
+    srv: ServerRC,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::path(POSITION_ROUTE_PATH))
+        .and(json_body::<handlers::PositionPayload>())
+        .and(with_server(srv))
+        .and_then(handlers::set_position)
+}
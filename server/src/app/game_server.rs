@@ -1,35 +1,153 @@
 use std::sync::Mutex;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 
-use game::Board;
+use game::{Board, FenMode, Game, Move, Search, SearchLimits};
+
+/// How long the computer thinks about its moves when not told otherwise.
+const DEFAULT_ENGINE_MOVE_TIME: Duration = Duration::from_secs(1);
+
+/// The most the computer may think about a move, whatever the client asks, so that a request
+/// can't keep the engine busy indefinitely.
+const MAX_ENGINE_MOVE_TIME: Duration = Duration::from_secs(10);
+const MAX_ENGINE_DEPTH: usize = 32;
+
+pub enum MoveNotation {
+    Squares {
+        src: String,
+        dst: String,
+        promotion: Option<char>,
+    },
+    San(String),
+}
 
 pub struct GameServer {
-    board: Mutex<Board>,
+    game: Mutex<Game>,
+    search: Mutex<Search>,
 }
 
 impl GameServer {
-    pub fn new() -> Self {
-        let board = Mutex::from(game::board_with_setup());
-        Self { board }
+    /// Creates a server whose engine keeps a transposition table of about `table_size_mb`
+    /// megabytes.
+    pub fn new(table_size_mb: usize) -> Self {
+        let game = Mutex::from(Game::new());
+        let search = Mutex::from(Search::with_table_size(table_size_mb));
+        Self { game, search }
+    }
+
+    /// Plays a move given either as a pair of squares or in SAN.
+    ///
+    /// Returns the new position along with the SAN of the move played and the status of the
+    /// game.
+    pub fn do_move(&self, mv: MoveNotation) -> Result<(String, String, String)> {
+        let mut guard = self.game.lock().map_err(|_e| anyhow!("Mutex poisoned"))?;
+        let game_ref = &mut *guard;
+        let mv = match mv {
+            MoveNotation::Squares {
+                src,
+                dst,
+                promotion,
+            } => {
+                println!("{} => {}", src, dst);
+                Move::from_notation(&src, &dst, promotion)?
+            }
+            MoveNotation::San(san) => {
+                println!("{}", san);
+                game_ref.position().parse_san(&san)?
+            }
+        };
+        let san = game_ref.position().to_san(&mv)?;
+        game_ref.play(&mv)?;
+        Ok((
+            game_ref.position().to_fen(),
+            san,
+            game_ref.status().to_string(),
+        ))
     }
 
-    pub fn do_move(
+    /// Lets the computer play a move, searching up to a depth and for a given time.
+    ///
+    /// Both are capped, and the search always has a time limit: its default one when given
+    /// neither, or the longest allowed when only given a depth. The search blocks, and runs on
+    /// a copy of the game so that the position can still be read meanwhile.
+    ///
+    /// Returns the same as `do_move`.
+    pub fn engine_move(
         &self,
-        start_square_notation: &str,
-        end_square_notation: &str,
-    ) -> Result<String> {
-        println!("{} => {}", start_square_notation, end_square_notation);
-        let mut guard = self.board.lock().map_err(|_e| anyhow!("Mutex poisoned"))?;
-        let board_ref = &mut *guard;
-        let new_position = board_ref.move_notation(start_square_notation, end_square_notation)?;
-        *board_ref = new_position;
-        Ok(board_ref.to_fen())
+        depth: Option<usize>,
+        time: Option<Duration>,
+    ) -> Result<(String, String, String)> {
+        let default_time = if depth.is_some() {
+            MAX_ENGINE_MOVE_TIME
+        } else {
+            DEFAULT_ENGINE_MOVE_TIME
+        };
+        let limits = SearchLimits {
+            depth: depth.map(|depth| depth.clamp(1, MAX_ENGINE_DEPTH)),
+            time: Some(time.unwrap_or(default_time).min(MAX_ENGINE_MOVE_TIME)),
+            ..Default::default()
+        };
+
+        let game = self
+            .game
+            .lock()
+            .map_err(|_e| anyhow!("Mutex poisoned"))?
+            .clone();
+        let result = self
+            .search
+            .lock()
+            .map_err(|_e| anyhow!("Mutex poisoned"))?
+            .run_game(&game, &limits);
+        let mv = result.best_move.ok_or_else(|| anyhow!("No move to play"))?;
+        let stats = &result.table_stats;
+        println!(
+            "{} ({}, depth {}, {} nodes, {:.1}% hash hits, {}/1000 hash full, {:.1}% first-move cutoffs)",
+            mv,
+            result.score,
+            result.depth,
+            result.nodes,
+            stats.hit_rate() * 100.0,
+            stats.hashfull,
+            result.ordering_stats.first_move_cutoff_rate() * 100.0
+        );
+
+        let mut guard = self.game.lock().map_err(|_e| anyhow!("Mutex poisoned"))?;
+        let game_ref = &mut *guard;
+        if game_ref.history().len() != game.history().len()
+            || game_ref.position().hash() != game.position().hash()
+        {
+            return Err(anyhow!(
+                "The position changed while the computer was thinking"
+            ));
+        }
+        let san = game_ref.position().to_san(&mv)?;
+        game_ref.play(&mv)?;
+        Ok((
+            game_ref.position().to_fen(),
+            san,
+            game_ref.status().to_string(),
+        ))
+    }
+
+    pub fn undo(&self) -> Result<String> {
+        let mut guard = self.game.lock().map_err(|_e| anyhow!("Mutex poisoned"))?;
+        let game_ref = &mut *guard;
+        game_ref.undo().ok_or_else(|| anyhow!("No move to undo"))?;
+        Ok(game_ref.position().to_fen())
+    }
+
+    /// Starts a new game from a position given in FEN, rejecting illegal positions.
+    pub fn set_position(&self, fen: &str) -> Result<String> {
+        let position = Board::from_fen_with_mode(fen, FenMode::Strict)?;
+        let mut guard = self.game.lock().map_err(|_e| anyhow!("Mutex poisoned"))?;
+        *guard = Game::from_position(position);
+        Ok(guard.position().to_fen())
     }
 
     pub fn get_position(&self) -> Result<String> {
-        let guard = self.board.lock().map_err(|_e| anyhow!("Mutex poisoned"))?;
-        let board_ref = &*guard;
-        Ok(board_ref.to_fen())
+        let guard = self.game.lock().map_err(|_e| anyhow!("Mutex poisoned"))?;
+        let game_ref = &*guard;
+        Ok(game_ref.position().to_fen())
     }
 }
@@ -1,28 +1,73 @@
 use std::convert::Infallible;
+use std::time::Duration;
 
+use anyhow::anyhow;
+use game::{MoveError, SanError};
 use serde::{Deserialize, Serialize};
 
-use super::ServerRC;
+use super::{game_server::MoveNotation, ServerRC};
 
 #[derive(Deserialize, Serialize)]
 struct MessageResponse {
     message: String,
 }
 
+/// A move, either as `src` & `dst` squares (with an optional `promotion`) or as `san`.
 #[derive(Deserialize, Serialize)]
 pub struct MovePayload {
-    src: String,
-    dst: String,
+    src: Option<String>,
+    dst: Option<String>,
+    promotion: Option<char>,
+    san: Option<String>,
 }
 
+/// How long the computer may think, searching its default time when neither is given. Both are
+/// capped by the server.
 #[derive(Deserialize, Serialize)]
-struct PositionResponse {
+pub struct EngineMovePayload {
+    depth: Option<usize>,
+    time_ms: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PositionPayload {
+    fen: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct MoveResponse {
     position_fen: String,
+    san: String,
+    status: String,
 }
 
 #[derive(Deserialize, Serialize)]
-struct ErrorResponse {
+struct PositionResponse {
+    position_fen: String,
+}
+
+/// The reason a move was refused, as a stable code along with its message.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum MoveFailure<'a> {
+    Move(&'a MoveError),
+    San(&'a SanError),
+}
+
+impl<'a> MoveFailure<'a> {
+    fn from_error(e: &'a anyhow::Error) -> Option<Self> {
+        e.downcast_ref::<MoveError>()
+            .map(MoveFailure::Move)
+            .or_else(|| e.downcast_ref::<SanError>().map(MoveFailure::San))
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorResponse<'a> {
     error: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    move_error: Option<MoveFailure<'a>>,
 }
 
 pub async fn hello(_srv: ServerRC) -> Result<impl warp::Reply, Infallible> {
@@ -32,21 +77,94 @@ pub async fn hello(_srv: ServerRC) -> Result<impl warp::Reply, Infallible> {
 }
 
 pub async fn do_move(item: MovePayload, srv: ServerRC) -> Result<impl warp::Reply, Infallible> {
-    Ok(match srv.do_move(&item.src, &item.dst) {
-        Ok(position_fen) => warp::reply::json(&PositionResponse { position_fen }),
+    let notation = match item {
+        MovePayload { san: Some(san), .. } => Some(MoveNotation::San(san)),
+        MovePayload {
+            src: Some(src),
+            dst: Some(dst),
+            promotion,
+            ..
+        } => Some(MoveNotation::Squares {
+            src,
+            dst,
+            promotion,
+        }),
+        _ => None,
+    };
+
+    let result = match notation {
+        Some(mv) => srv.do_move(mv),
+        None => Err(anyhow!("Expected either src & dst or san")),
+    };
+
+    Ok(match result {
+        Ok((position_fen, san, status)) => warp::reply::json(&MoveResponse {
+            position_fen,
+            san,
+            status,
+        }),
         Err(e) => {
             let x = e.to_string();
             println!("Error: {}", x);
-            warp::reply::json(&ErrorResponse { error: x })
+            warp::reply::json(&ErrorResponse {
+                error: x,
+                move_error: MoveFailure::from_error(&e),
+            })
         }
     })
 }
 
+pub async fn engine_move(
+    item: EngineMovePayload,
+    srv: ServerRC,
+) -> Result<impl warp::Reply, Infallible> {
+    let time = item.time_ms.map(Duration::from_millis);
+    // The search blocks, so it runs off the threads serving requests.
+    let result = tokio::task::spawn_blocking(move || srv.engine_move(item.depth, time))
+        .await
+        .unwrap_or_else(|e| Err(anyhow!("The search failed: {}", e)));
+    Ok(match result {
+        Ok((position_fen, san, status)) => warp::reply::json(&MoveResponse {
+            position_fen,
+            san,
+            status,
+        }),
+        Err(e) => warp::reply::json(&ErrorResponse {
+            error: e.to_string(),
+            move_error: None,
+        }),
+    })
+}
+
+pub async fn undo(srv: ServerRC) -> Result<impl warp::Reply, Infallible> {
+    Ok(match srv.undo() {
+        Ok(position_fen) => warp::reply::json(&PositionResponse { position_fen }),
+        Err(e) => warp::reply::json(&ErrorResponse {
+            error: e.to_string(),
+            move_error: None,
+        }),
+    })
+}
+
 pub async fn get_position(srv: ServerRC) -> Result<impl warp::Reply, Infallible> {
     Ok(match srv.get_position() {
         Ok(position_fen) => warp::reply::json(&PositionResponse { position_fen }),
         Err(e) => warp::reply::json(&ErrorResponse {
             error: e.to_string(),
+            move_error: None,
+        }),
+    })
+}
+
+pub async fn set_position(
+    item: PositionPayload,
+    srv: ServerRC,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(match srv.set_position(&item.fen) {
+        Ok(position_fen) => warp::reply::json(&PositionResponse { position_fen }),
+        Err(e) => warp::reply::json(&ErrorResponse {
+            error: e.to_string(),
+            move_error: None,
         }),
     })
 }
@@ -4,12 +4,22 @@ mod handlers;
 
 use game_server::GameServer;
 
+use std::env;
 use std::sync::Arc;
 
+use game::DEFAULT_TABLE_SIZE_MB;
+
 type ServerRC = Arc<GameServer>;
 
+/// Environment variable setting the size of the engine's transposition table, in megabytes.
+const TABLE_SIZE_VAR: &str = "ENGINE_HASH_MB";
+
 pub async fn serve() {
-    let server = Arc::new(GameServer::new());
+    let table_size_mb = env::var(TABLE_SIZE_VAR)
+        .ok()
+        .and_then(|size| size.parse().ok())
+        .unwrap_or(DEFAULT_TABLE_SIZE_MB);
+    let server = Arc::new(GameServer::new(table_size_mb));
     warp::serve(filters::all(server))
         .run(([127, 0, 0, 1], 3030))
         .await